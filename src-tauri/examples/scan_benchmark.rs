@@ -0,0 +1,127 @@
+// Scan benchmark - times directory discovery over a synthetic tree of repositories
+//
+// Usage: cargo run --release --example scan_benchmark -- [repositories] [depth]
+//
+// Builds `repositories` Git repositories (default 200), each with a few hundred nested
+// directories, next to unversioned projects, then compares the ancestor walk discovery
+// used to do (open every ancestor of every directory as a repository) with
+// `RepositoryService::discover_directories`, which stops at repository roots.
+use repo_manager::adapters::{GitAdapter, IgnoreAdapter};
+use repo_manager::models::{ScanDepth, ScanRules};
+use repo_manager::services::{RepositoryService, ScanHandle};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const REPOS_PER_GROUP: usize = 10;
+const PLAIN_PROJECTS_PER_GROUP: usize = 3;
+/// Directories per level inside each project, e.g. 4 -> 4 + 16 + 64 + 256 directories
+const FAN_OUT: usize = 4;
+const NESTING: usize = 4;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let repo_count: usize = args.next().map(|arg| arg.parse()).transpose()?.unwrap_or(200);
+    let depth = match args.next().map(|arg| arg.parse()).transpose()? {
+        Some(levels) => ScanDepth::Levels(levels),
+        None => ScanDepth::UntilRepository,
+    };
+
+    let base_path = std::env::temp_dir().join(format!("repo-manager-scan-benchmark-{}", std::process::id()));
+    let result = run(&base_path, repo_count, depth);
+    let _ = fs::remove_dir_all(&base_path);
+    result
+}
+
+fn run(base_path: &Path, repo_count: usize, depth: ScanDepth) -> Result<(), Box<dyn std::error::Error>> {
+    println!("BENCHMARK: Building synthetic tree in {}", base_path.display());
+    let dir_count = build_tree(base_path, repo_count)?;
+    println!("BENCHMARK: {} repositories, {} directories, depth {:?}", repo_count, dir_count, depth);
+
+    let rules = ScanRules { depth, ..ScanRules::default() };
+
+    let (mut legacy_dirs, legacy_time) = timed(|| legacy_discover(base_path, &rules));
+    let service = RepositoryService::new();
+    let (mut dirs, time) = timed(|| service.discover_directories(base_path, &rules, &ScanHandle::new()));
+
+    legacy_dirs.sort();
+    dirs.sort();
+    if legacy_dirs != dirs {
+        return Err(format!("results differ: ancestor walk found {}, discovery found {}", legacy_dirs.len(), dirs.len()).into());
+    }
+
+    println!("BENCHMARK: Both found {} directories", dirs.len());
+    println!("BENCHMARK: Ancestor walk: {:?}", legacy_time);
+    println!("BENCHMARK: Discovery:     {:?}", time);
+    println!("BENCHMARK: Speedup:       {:.1}x", legacy_time.as_secs_f64() / time.as_secs_f64().max(f64::EPSILON));
+    Ok(())
+}
+
+/// Groups of repositories and unversioned projects, each with `NESTING` levels of
+/// subdirectories. Returns the number of directories created.
+fn build_tree(base_path: &Path, repo_count: usize) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut dir_count = 0;
+
+    for repo_index in 0..repo_count {
+        let group = base_path.join(format!("group-{}", repo_index / REPOS_PER_GROUP));
+        let repo_path = group.join(format!("repo-{}", repo_index));
+        git2::Repository::init(&repo_path)?;
+        dir_count += 1 + build_nested(&repo_path, NESTING)?;
+
+        if repo_index % REPOS_PER_GROUP < PLAIN_PROJECTS_PER_GROUP {
+            let project_path = group.join(format!("project-{}", repo_index));
+            fs::create_dir_all(&project_path)?;
+            fs::write(project_path.join("README.md"), "synthetic project\n")?;
+            dir_count += 1 + build_nested(&project_path, 1)?;
+        }
+    }
+
+    Ok(dir_count)
+}
+
+fn build_nested(parent: &Path, levels: usize) -> std::io::Result<usize> {
+    if levels == 0 {
+        return Ok(0);
+    }
+
+    let mut dir_count = 0;
+    for index in 0..FAN_OUT {
+        let dir_path = parent.join(format!("module-{}", index));
+        fs::create_dir_all(&dir_path)?;
+        fs::write(dir_path.join("mod.rs"), "pub fn f() {}\n")?;
+        dir_count += 1 + build_nested(&dir_path, levels - 1)?;
+    }
+    Ok(dir_count)
+}
+
+/// First pass as it was before repository roots were pruned: walk everything, then
+/// reject directories that have a repository among their ancestors
+fn legacy_discover(base_path: &Path, rules: &ScanRules) -> Vec<PathBuf> {
+    let git_adapter = GitAdapter::new();
+    let ignore_adapter = IgnoreAdapter::with_rules(rules);
+
+    ignore::WalkBuilder::new(base_path)
+        .max_depth(rules.depth_for(base_path).max_depth())
+        .hidden(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.into_path())
+        .filter(|path| !ignore_adapter.should_skip_directory(path))
+        .filter(|path| !is_inside_git_repo(&git_adapter, path, base_path))
+        .collect()
+}
+
+fn is_inside_git_repo(git_adapter: &GitAdapter, path: &Path, base_path: &Path) -> bool {
+    path.ancestors()
+        .skip(1)
+        .take_while(|parent| *parent != base_path)
+        .any(|parent| git_adapter.is_git_repository(parent))
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
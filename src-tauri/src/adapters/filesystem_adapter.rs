@@ -5,6 +5,7 @@ use walkdir::WalkDir;
 use chrono::{DateTime, Utc};
 use humansize::{format_size, DECIMAL};
 
+#[derive(Default)]
 pub struct FilesystemAdapter;
 
 impl FilesystemAdapter {
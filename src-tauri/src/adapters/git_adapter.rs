@@ -1,9 +1,32 @@
 // Git operations adapter - wraps git2/gix libraries
 use crate::models::*;
-use git2::{Repository as GitRepository, StatusOptions, BranchType, ErrorCode};
-use std::path::Path;
-use chrono::{DateTime, Utc};
+use git2::{Repository as GitRepository, RepositoryState, StatusOptions, Branch, BranchType, ErrorCode, Oid, Sort, WorktreeLockStatus};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::OsStr;
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, Utc};
+use tokio::process::Command;
 
+/// Commit counts stop at this many commits so huge histories stay cheap to analyze
+pub const COMMIT_COUNT_LIMIT: usize = 50_000;
+
+/// How long fetch, pull, push and the other built-in CLI operations may run before git is killed
+pub const GIT_CLI_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Branch names tried, in order, when `origin/HEAD` doesn't name the default branch
+const DEFAULT_BRANCH_CANDIDATES: [&str; 4] = ["main", "master", "develop", "trunk"];
+
+/// Commits diffed per activity window, so a burst of history can't stall the request
+pub const ACTIVITY_COMMIT_LIMIT: usize = 10_000;
+
+/// Files larger than this are reported as binary instead of being diffed line by line
+pub const DIFF_MAX_FILE_BYTES: i64 = 4 * 1024 * 1024;
+
+#[derive(Default)]
 pub struct GitAdapter;
 
 impl GitAdapter {
@@ -11,40 +34,79 @@ impl GitAdapter {
         Self
     }
 
+    /// Staged, unstaged, untracked and conflicted paths. `files` is left empty; scans use
+    /// this, so ignored paths aren't listed and renames aren't detected.
     pub async fn get_status(&self, repo_path: &Path) -> Result<GitStatus, Box<dyn std::error::Error>> {
+        self.read_status(repo_path, false)
+    }
+
+    /// Status with `files` filled in, including ignored paths and detected renames
+    pub async fn get_status_detailed(&self, repo_path: &Path) -> Result<GitStatus, Box<dyn std::error::Error>> {
+        self.read_status(repo_path, true)
+    }
+
+    fn read_status(&self, repo_path: &Path, detailed: bool) -> Result<GitStatus, Box<dyn std::error::Error>> {
         let repo = GitRepository::open(repo_path)?;
         
         let mut opts = StatusOptions::new();
-        opts.include_untracked(true);
+        opts.include_untracked(true)
+            .include_ignored(detailed)
+            .renames_head_to_index(detailed)
+            .renames_index_to_workdir(detailed);
         
         let statuses = repo.statuses(Some(&mut opts))?;
+        let submodules = if detailed { Self::submodule_names_by_path(&repo) } else { HashMap::new() };
+        let workdir = repo.workdir().map(|dir| dir.to_path_buf());
         
+        let mut files = Vec::new();
         let mut staged_files = Vec::new();
         let mut unstaged_files = Vec::new();
         let mut untracked_files = Vec::new();
+        let mut conflicted_files = Vec::new();
         
         for status in statuses.iter() {
-            let file_path = status.path().unwrap_or("").to_string();
             let status_flags = status.status();
+            let old_side = |delta: Option<git2::DiffDelta>| delta
+                .and_then(|delta| delta.old_file().path().map(|p| p.to_string_lossy().to_string()));
+            let entry = detailed.then(|| Self::file_status_entry(&repo, &status, &submodules, workdir.as_deref()));
+            let path = match &entry {
+                Some(entry) => entry.path.clone(),
+                None => status.path().map(|p| p.to_string()).unwrap_or_default(),
+            };
             
-            if status_flags.is_index_new() || 
-               status_flags.is_index_modified() || 
-               status_flags.is_index_deleted() ||
-               status_flags.is_index_renamed() ||
-               status_flags.is_index_typechange() {
-                staged_files.push(file_path.clone());
-            }
-            
-            if status_flags.is_wt_modified() ||
-               status_flags.is_wt_deleted() ||
-               status_flags.is_wt_renamed() ||
-               status_flags.is_wt_typechange() {
-                unstaged_files.push(file_path.clone());
+            if status_flags.is_conflicted() {
+                conflicted_files.push(path.clone());
+            } else if !status_flags.is_ignored() {
+                // Renames list both sides, as they did before renames were detected: a staged
+                // rename as two staged paths, an unstaged one as a deletion and a new file
+                if status_flags.is_index_new() || 
+                   status_flags.is_index_modified() || 
+                   status_flags.is_index_deleted() ||
+                   status_flags.is_index_renamed() ||
+                   status_flags.is_index_typechange() {
+                    if let Some(old_path) = old_side(status.head_to_index()).filter(|_| status_flags.is_index_renamed()) {
+                        staged_files.push(old_path);
+                    }
+                    staged_files.push(path.clone());
+                }
+                
+                if status_flags.is_wt_renamed() {
+                    if let Some(old_path) = old_side(status.index_to_workdir()) {
+                        unstaged_files.push(old_path);
+                    }
+                    untracked_files.push(path.clone());
+                } else if status_flags.is_wt_modified() ||
+                   status_flags.is_wt_deleted() ||
+                   status_flags.is_wt_typechange() {
+                    unstaged_files.push(path.clone());
+                }
+                
+                if status_flags.is_wt_new() {
+                    untracked_files.push(path.clone());
+                }
             }
             
-            if status_flags.is_wt_new() {
-                untracked_files.push(file_path);
-            }
+            files.extend(entry);
         }
         
         let current_branch = match repo.head() {
@@ -52,22 +114,22 @@ impl GitAdapter {
             Err(_) => None,
         };
         
-        let tracking_branch = if let (Ok(_head), Some(ref branch_name)) = (repo.head(), &current_branch) {
+        let (tracking_branch, ahead, behind) = if let (Ok(_head), Some(ref branch_name)) = (repo.head(), &current_branch) {
             if let Ok(branch) = repo.find_branch(branch_name, BranchType::Local) {
+                let (ahead, behind) = self.branch_ahead_behind(&repo, &branch);
                 if let Ok(upstream) = branch.upstream() {
-                    upstream.name().ok().flatten().map(|s| s.to_string())
+                    (upstream.name().ok().flatten().map(|s| s.to_string()), ahead, behind)
                 } else {
-                    None
+                    (None, ahead, behind)
                 }
             } else {
-                None
+                (None, 0, 0)
             }
         } else {
-            None
+            (None, 0, 0)
         };
         
-        let (ahead, behind) = (0, 0);
-        let is_clean = staged_files.is_empty() && unstaged_files.is_empty() && untracked_files.is_empty();
+        let is_clean = staged_files.is_empty() && unstaged_files.is_empty() && untracked_files.is_empty() && conflicted_files.is_empty();
         
         Ok(GitStatus {
             is_clean,
@@ -78,25 +140,277 @@ impl GitAdapter {
             behind,
             current_branch,
             tracking_branch,
+            operation_in_progress: Self::operation_in_progress(repo.state()),
+            conflicted_files,
+            files,
         })
     }
+    
+    /// Build the detailed status entry for one path
+    fn file_status_entry(repo: &GitRepository, status: &git2::StatusEntry, submodules: &HashMap<String, String>, workdir: Option<&Path>) -> FileStatusEntry {
+        let flags = status.status();
+        let delta_path = |delta: Option<git2::DiffDelta>, new_side: bool| delta.and_then(|delta| {
+            let file = if new_side { delta.new_file() } else { delta.old_file() };
+            file.path().map(|p| p.to_string_lossy().to_string())
+        });
+        
+        let path = delta_path(status.index_to_workdir(), true)
+            .or_else(|| delta_path(status.head_to_index(), true))
+            .or_else(|| status.path().map(|p| p.to_string()))
+            .unwrap_or_default();
+        let old_path = if flags.is_index_renamed() {
+            delta_path(status.head_to_index(), false)
+        } else if flags.is_wt_renamed() {
+            delta_path(status.index_to_workdir(), false)
+        } else {
+            None
+        };
+        
+        let index_status = if flags.is_index_new() {
+            Some(FileChangeStatus::Added)
+        } else if flags.is_index_modified() {
+            Some(FileChangeStatus::Modified)
+        } else if flags.is_index_deleted() {
+            Some(FileChangeStatus::Deleted)
+        } else if flags.is_index_renamed() {
+            Some(FileChangeStatus::Renamed)
+        } else if flags.is_index_typechange() {
+            Some(FileChangeStatus::TypeChange)
+        } else {
+            None
+        };
+        let worktree_status = if flags.is_wt_new() {
+            Some(FileChangeStatus::Untracked)
+        } else if flags.is_wt_modified() {
+            Some(FileChangeStatus::Modified)
+        } else if flags.is_wt_deleted() {
+            Some(FileChangeStatus::Deleted)
+        } else if flags.is_wt_renamed() {
+            Some(FileChangeStatus::Renamed)
+        } else if flags.is_wt_typechange() {
+            Some(FileChangeStatus::TypeChange)
+        } else {
+            None
+        };
+        
+        let size_bytes = workdir
+            .and_then(|workdir| workdir.join(&path).symlink_metadata().ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len());
+        
+        let submodule = submodules.get(path.trim_end_matches('/'))
+            .and_then(|name| repo.submodule_status(name, git2::SubmoduleIgnore::None).ok())
+            .map(|submodule_status| SubmoduleChanges {
+                new_commits: submodule_status.contains(git2::SubmoduleStatus::WD_MODIFIED),
+                modified_content: submodule_status.intersects(git2::SubmoduleStatus::WD_INDEX_MODIFIED | git2::SubmoduleStatus::WD_WD_MODIFIED),
+                untracked_content: submodule_status.contains(git2::SubmoduleStatus::WD_UNTRACKED),
+            });
+        
+        FileStatusEntry {
+            path,
+            old_path,
+            index_status,
+            worktree_status,
+            is_conflicted: flags.is_conflicted(),
+            is_ignored: flags.is_ignored(),
+            size_bytes,
+            submodule,
+        }
+    }
+    
+    /// List the repository's submodules with their recorded and checked-out commits
+    pub fn list_submodules(&self, repo_path: &Path) -> Result<Vec<SubmoduleInfo>, git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let mut submodules = Vec::new();
+        
+        for submodule in repo.submodules()? {
+            let name = submodule.name().unwrap_or("").to_string();
+            let status = repo.submodule_status(&name, git2::SubmoduleIgnore::None)
+                .unwrap_or(git2::SubmoduleStatus::empty());
+            
+            submodules.push(SubmoduleInfo {
+                path: submodule.path().to_string_lossy().to_string(),
+                url: submodule.url().map(|url| url.to_string()),
+                recorded_sha: submodule.index_id().or(submodule.head_id()).map(|oid| oid.to_string()),
+                checked_out_sha: submodule.workdir_id().map(|oid| oid.to_string()),
+                is_initialized: status.contains(git2::SubmoduleStatus::IN_CONFIG)
+                    && !status.contains(git2::SubmoduleStatus::WD_UNINITIALIZED),
+                is_dirty: status.intersects(
+                    git2::SubmoduleStatus::WD_INDEX_MODIFIED
+                        | git2::SubmoduleStatus::WD_WD_MODIFIED
+                        | git2::SubmoduleStatus::WD_UNTRACKED
+                ),
+                name,
+            });
+        }
+        
+        submodules.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(submodules)
+    }
+    
+    /// Determine how the repository at `repo_path` is laid out. None if it isn't a repository.
+    pub fn get_repo_kind(&self, repo_path: &Path) -> Option<RepoKind> {
+        let repo = GitRepository::open(repo_path).ok()?;
+        
+        if repo.is_bare() {
+            return Some(RepoKind::Bare);
+        }
+        if repo.is_worktree() {
+            return Some(RepoKind::Worktree { main_repo: Self::main_repo_path(&Self::common_dir(&repo)) });
+        }
+        if let Some(parent_repo) = Self::superproject_path(&repo) {
+            return Some(RepoKind::Submodule { parent_repo });
+        }
+        Some(RepoKind::Normal)
+    }
+    
+    /// Check if `path` is the Git directory of a repository whose working tree is elsewhere
+    /// (`git init --separate-git-dir`); such repositories are reported at their working tree
+    pub fn is_detached_git_dir(&self, path: &Path) -> bool {
+        // Every Git directory has a HEAD file; checking it first avoids opening a repository
+        // for each directory the scan walks
+        if !path.join("HEAD").is_file() {
+            return false;
+        }
+        
+        let Ok(repo) = GitRepository::open(path) else {
+            return false;
+        };
+        
+        let same_path = match (repo.path().canonicalize(), path.canonicalize()) {
+            (Ok(git_dir), Ok(path)) => git_dir == path,
+            _ => false,
+        };
+        !repo.is_bare() && same_path
+    }
+    
+    /// The repository's Git directory: `.git` itself, or the directory a `.git` file points
+    /// to in linked worktrees and submodule checkouts
+    pub fn git_dir(&self, repo_path: &Path) -> Option<PathBuf> {
+        GitRepository::open(repo_path).ok().map(|repo| repo.path().components().collect())
+    }
+    
+    /// List the repository's linked worktrees with the branch each has checked out
+    pub fn list_worktrees(&self, repo_path: &Path) -> Result<Vec<WorktreeInfo>, git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let mut worktrees = Vec::new();
+        
+        for name in repo.worktrees()?.iter().flatten() {
+            let Ok(worktree) = repo.find_worktree(name) else {
+                continue;
+            };
+            let checkout = GitRepository::open_from_worktree(&worktree).ok();
+            let head = checkout.as_ref().and_then(|checkout| checkout.head().ok());
+            
+            worktrees.push(WorktreeInfo {
+                name: name.to_string(),
+                path: Self::normalized_path(worktree.path()),
+                branch: head.as_ref()
+                    .filter(|head| head.is_branch())
+                    .and_then(|head| head.shorthand().map(|branch| branch.to_string())),
+                head_sha: head.as_ref().and_then(|head| head.target()).map(|oid| oid.to_string()),
+                is_locked: matches!(worktree.is_locked(), Ok(WorktreeLockStatus::Locked(_))),
+                is_missing: worktree.validate().is_err(),
+            });
+        }
+        
+        worktrees.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(worktrees)
+    }
+    
+    /// Git directory shared by all worktrees of `repo`: the `commondir` file of a linked
+    /// worktree points to it, any other repository's Git directory is its own. The path is
+    /// resolved lexically rather than canonicalized so it stays comparable with the paths
+    /// git2 and the scan report, which keep symlinks and platform prefixes as they were given.
+    fn common_dir(repo: &GitRepository) -> PathBuf {
+        match std::fs::read_to_string(repo.path().join("commondir")) {
+            Ok(common_dir) => Self::resolve_dots(&repo.path().join(common_dir.trim())),
+            Err(_) => repo.path().to_path_buf(),
+        }
+    }
+    
+    /// Drop `.` components and apply `..` to the preceding component
+    fn resolve_dots(path: &Path) -> PathBuf {
+        let mut resolved = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::CurDir => {},
+                Component::ParentDir if matches!(resolved.components().next_back(), Some(Component::Normal(_))) => {
+                    resolved.pop();
+                },
+                component => resolved.push(component),
+            }
+        }
+        resolved
+    }
+    
+    /// Working tree of the repository owning the common Git directory `commondir`;
+    /// for a bare main repository that's the Git directory itself
+    fn main_repo_path(commondir: &Path) -> String {
+        match commondir.file_name() {
+            Some(name) if name == ".git" => Self::normalized_path(commondir.parent().unwrap_or(commondir)),
+            _ => Self::normalized_path(commondir),
+        }
+    }
+    
+    /// Working tree of the superproject if `repo` is a submodule checkout: its `.git` is a
+    /// gitdir file pointing into another repository's `modules` directory
+    fn superproject_path(repo: &GitRepository) -> Option<String> {
+        let workdir = repo.workdir()?;
+        if !workdir.join(".git").is_file() {
+            return None;
+        }
+        
+        let parent = GitRepository::discover(workdir.parent()?).ok()?;
+        if !repo.path().starts_with(Self::common_dir(&parent).join("modules")) {
+            return None;
+        }
+        parent.workdir().map(Self::normalized_path)
+    }
+    
+    /// Path as a string without the trailing separator git2 leaves on directories
+    fn normalized_path(path: &Path) -> String {
+        path.components().collect::<PathBuf>().to_string_lossy().to_string()
+    }
+    
+    /// Submodule names keyed by their path in the working tree
+    fn submodule_names_by_path(repo: &GitRepository) -> HashMap<String, String> {
+        repo.submodules().unwrap_or_default()
+            .iter()
+            .filter_map(|submodule| {
+                let name = submodule.name()?.to_string();
+                Some((submodule.path().to_string_lossy().to_string(), name))
+            })
+            .collect()
+    }
+    
+    /// Map the repository state to the operation it is in the middle of
+    fn operation_in_progress(state: RepositoryState) -> Option<GitOperation> {
+        match state {
+            RepositoryState::Clean => None,
+            RepositoryState::Merge => Some(GitOperation::Merge),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(GitOperation::Revert),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some(GitOperation::CherryPick),
+            RepositoryState::Bisect => Some(GitOperation::Bisect),
+            RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => Some(GitOperation::Rebase),
+            RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some(GitOperation::ApplyMailbox),
+        }
+    }
 
     pub async fn get_remotes(&self, repo_path: &Path) -> Result<Vec<RemoteInfo>, Box<dyn std::error::Error>> {
         let repo = GitRepository::open(repo_path)?;
         let remotes = repo.remotes()?;
         let mut remote_info = Vec::new();
         
-        for remote_name in remotes.iter() {
-            if let Some(name) = remote_name {
-                if let Ok(remote) = repo.find_remote(name) {
-                    let info = RemoteInfo {
-                        name: name.to_string(),
-                        url: remote.url().unwrap_or("").to_string(),
-                        fetch_url: remote.url().map(|s| s.to_string()),
-                        push_url: remote.pushurl().map(|s| s.to_string()),
-                    };
-                    remote_info.push(info);
-                }
+        for name in remotes.iter().flatten() {
+            if let Ok(remote) = repo.find_remote(name) {
+                let info = RemoteInfo {
+                    name: name.to_string(),
+                    url: remote.url().unwrap_or("").to_string(),
+                    fetch_url: remote.url().map(|s| s.to_string()),
+                    push_url: remote.pushurl().map(|s| s.to_string()),
+                };
+                remote_info.push(info);
             }
         }
         
@@ -116,33 +430,33 @@ impl GitAdapter {
         let local_branches = repo.branches(Some(BranchType::Local))?;
         for branch_result in local_branches {
             let (branch, _branch_type) = branch_result?;
-            if let Ok(name) = branch.name() {
-                if let Some(name_str) = name {
-                    let is_current = current_branch_name.as_ref() == Some(&name_str.to_string());
-                    
-                    let upstream = if let Ok(upstream_branch) = branch.upstream() {
-                        upstream_branch.name().ok().flatten().map(|s| s.to_string())
-                    } else {
-                        None
-                    };
-                    
-                    let last_commit = if let Ok(commit) = branch.get().peel_to_commit() {
-                        let time = commit.time();
-                        DateTime::from_timestamp(time.seconds(), 0)
-                    } else {
-                        None
-                    };
-                    
-                    branches.push(BranchInfo {
-                        name: name_str.to_string(),
-                        is_current,
-                        is_remote: false,
-                        upstream,
-                        last_commit,
-                        ahead: 0,
-                        behind: 0,
-                    });
-                }
+            if let Ok(Some(name_str)) = branch.name() {
+                let is_current = current_branch_name.as_ref() == Some(&name_str.to_string());
+                
+                let upstream = if let Ok(upstream_branch) = branch.upstream() {
+                    upstream_branch.name().ok().flatten().map(|s| s.to_string())
+                } else {
+                    None
+                };
+                
+                let last_commit = if let Ok(commit) = branch.get().peel_to_commit() {
+                    let time = commit.time();
+                    DateTime::from_timestamp(time.seconds(), 0)
+                } else {
+                    None
+                };
+                
+                let (ahead, behind) = self.branch_ahead_behind(&repo, &branch);
+                
+                branches.push(BranchInfo {
+                    name: name_str.to_string(),
+                    is_current,
+                    is_remote: false,
+                    upstream,
+                    last_commit,
+                    ahead,
+                    behind,
+                });
             }
         }
         
@@ -150,31 +464,1060 @@ impl GitAdapter {
         let remote_branches = repo.branches(Some(BranchType::Remote))?;
         for branch_result in remote_branches {
             let (branch, _) = branch_result?;
-            if let Ok(name) = branch.name() {
-                if let Some(name_str) = name {
-                    let last_commit = if let Ok(commit) = branch.get().peel_to_commit() {
-                        let time = commit.time();
-                        DateTime::from_timestamp(time.seconds(), 0)
-                    } else {
-                        None
-                    };
-                    
-                    branches.push(BranchInfo {
-                        name: name_str.to_string(),
-                        is_current: false,
-                        is_remote: true,
-                        upstream: None,
-                        last_commit,
-                        ahead: 0,
-                        behind: 0,
-                    });
-                }
+            if let Ok(Some(name_str)) = branch.name() {
+                let last_commit = if let Ok(commit) = branch.get().peel_to_commit() {
+                    let time = commit.time();
+                    DateTime::from_timestamp(time.seconds(), 0)
+                } else {
+                    None
+                };
+                
+                branches.push(BranchInfo {
+                    name: name_str.to_string(),
+                    is_current: false,
+                    is_remote: true,
+                    upstream: None,
+                    last_commit,
+                    ahead: 0,
+                    behind: 0,
+                });
             }
         }
         
         Ok(branches)
     }
     
+    /// Count commits the local branch is ahead of / behind its upstream.
+    /// Branches without an upstream (or with an unresolvable tip) report (0, 0).
+    fn branch_ahead_behind(&self, repo: &GitRepository, branch: &Branch) -> (usize, usize) {
+        let local_oid = branch.get().target();
+        let upstream_oid = branch.upstream().ok().and_then(|upstream| upstream.get().target());
+        
+        match (local_oid, upstream_oid) {
+            (Some(local), Some(upstream)) => repo.graph_ahead_behind(local, upstream).unwrap_or((0, 0)),
+            _ => (0, 0),
+        }
+    }
+    
+    /// Find local branches that are merged into the default branch, whose upstream is gone,
+    /// or without commits in the last `inactive_days` days. The default branch itself is skipped.
+    pub fn get_stale_branches(&self, repo_path: &Path, inactive_days: u32) -> Result<Vec<StaleBranch>, git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let config = repo.config()?;
+        let default_branch = Self::find_default_branch(&repo);
+        let cutoff = Utc::now() - ChronoDuration::days(inactive_days as i64);
+        let mut stale_branches = Vec::new();
+        
+        for (branch, _) in repo.branches(Some(BranchType::Local))?.flatten() {
+            let (Some(name), Some(tip)) = (branch.name().ok().flatten().map(|s| s.to_string()), branch.get().target()) else {
+                continue;
+            };
+            if default_branch.as_ref().is_some_and(|default| default.name == name) {
+                continue;
+            }
+            
+            let mut reasons = Vec::new();
+            if let Some(default) = &default_branch {
+                let merged = default.tips().any(|default_tip| {
+                    default_tip == tip || repo.graph_descendant_of(default_tip, tip).unwrap_or(false)
+                });
+                if merged {
+                    reasons.push(StaleReason::Merged);
+                }
+            }
+            
+            let upstream = branch.upstream().ok().and_then(|upstream| upstream.name().ok().flatten().map(|s| s.to_string()));
+            let has_configured_upstream = config.get_string(&format!("branch.{}.merge", name)).is_ok();
+            if upstream.is_none() && has_configured_upstream {
+                reasons.push(StaleReason::UpstreamGone);
+            }
+            
+            let last_commit = repo.find_commit(tip).ok().and_then(|commit| DateTime::from_timestamp(commit.time().seconds(), 0));
+            if last_commit.is_some_and(|last_commit| last_commit < cutoff) {
+                reasons.push(StaleReason::Inactive);
+            }
+            
+            if reasons.is_empty() {
+                continue;
+            }
+            
+            stale_branches.push(StaleBranch {
+                repo_path: repo_path.to_string_lossy().to_string(),
+                branch: name,
+                tip_sha: tip.to_string(),
+                last_commit,
+                upstream,
+                is_current: branch.is_head(),
+                reasons,
+            });
+        }
+        
+        Ok(stale_branches)
+    }
+    
+    /// Delete a local branch if its tip is still `expected_tip`, returning a record of the tip
+    /// so it can be restored. With `dry_run` only the checks run.
+    pub fn delete_branch(&self, repo_path: &Path, name: &str, expected_tip: &str, dry_run: bool) -> Result<DeletedBranchRecord, Box<dyn std::error::Error + Send + Sync>> {
+        let repo = GitRepository::open(repo_path)?;
+        let mut branch = repo.find_branch(name, BranchType::Local)?;
+        
+        if branch.is_head() {
+            return Err(format!("{} is checked out", name).into());
+        }
+        
+        let tip_sha = branch.get().target().map(|oid| oid.to_string()).unwrap_or_default();
+        if tip_sha != expected_tip {
+            return Err(format!("{} moved to {} since it was reported", name, tip_sha).into());
+        }
+        
+        let upstream = branch.upstream().ok().and_then(|upstream| upstream.name().ok().flatten().map(|s| s.to_string()));
+        if !dry_run {
+            branch.delete()?;
+        }
+        
+        Ok(DeletedBranchRecord {
+            repo_path: repo_path.to_string_lossy().to_string(),
+            branch: name.to_string(),
+            tip_sha,
+            upstream,
+            deleted_at: Utc::now(),
+        })
+    }
+    
+    /// Recreate a deleted branch at its recorded tip. Fails if the branch exists again.
+    pub fn restore_branch(&self, repo_path: &Path, name: &str, tip_sha: &str) -> Result<(), git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let commit = repo.find_commit(Oid::from_str(tip_sha)?)?;
+        repo.branch(name, &commit, false)?;
+        Ok(())
+    }
+    
+    /// Find the default branch and how far HEAD has diverged from it. The comparison uses
+    /// origin's copy of the default branch when there is one, since the local branch may be stale.
+    pub fn get_default_branch_status(&self, repo_path: &Path) -> Option<DefaultBranchStatus> {
+        let repo = GitRepository::open(repo_path).ok()?;
+        let default_branch = Self::find_default_branch(&repo)?;
+        
+        let head = repo.head().ok();
+        let is_current = head.as_ref()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand())
+            .is_some_and(|name| name == default_branch.name);
+        
+        let head_oid = head.and_then(|head| head.target());
+        let default_oid = default_branch.remote.or(default_branch.local);
+        let (ahead, behind) = match (head_oid, default_oid) {
+            (Some(head_oid), Some(default_oid)) => repo.graph_ahead_behind(head_oid, default_oid).unwrap_or((0, 0)),
+            _ => (0, 0),
+        };
+        
+        Some(DefaultBranchStatus {
+            name: default_branch.name,
+            is_current,
+            ahead,
+            behind,
+        })
+    }
+    
+    /// The default branch: whatever `origin/HEAD` points to, otherwise the first of
+    /// `DEFAULT_BRANCH_CANDIDATES` that exists locally or on origin
+    fn find_default_branch(repo: &GitRepository) -> Option<DefaultBranch> {
+        let origin_head = repo.find_reference("refs/remotes/origin/HEAD").ok()
+            .and_then(|reference| reference.symbolic_target().map(|target| target.to_string()))
+            .and_then(|target| target.strip_prefix("refs/remotes/origin/").map(|name| name.to_string()));
+        
+        let candidates = origin_head.into_iter()
+            .chain(DEFAULT_BRANCH_CANDIDATES.iter().map(|name| name.to_string()));
+        
+        for name in candidates {
+            let local = repo.find_branch(&name, BranchType::Local).ok().and_then(|branch| branch.get().target());
+            let remote = repo.find_reference(&format!("refs/remotes/origin/{}", name)).ok().and_then(|reference| reference.target());
+            if local.is_some() || remote.is_some() {
+                return Some(DefaultBranch { name, local, remote });
+            }
+        }
+        
+        None
+    }
+    
+    /// Diff a single file: index against HEAD when `staged`, otherwise the working tree
+    /// against the index (untracked files show as fully added). `file_path` is relative to
+    /// the repository root and may be either side of a rename.
+    pub fn get_diff(&self, repo_path: &Path, file_path: &str, staged: bool) -> Result<FileDiff, git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        
+        let mut opts = git2::DiffOptions::new();
+        opts.max_size(DIFF_MAX_FILE_BYTES);
+        
+        // The whole tree is diffed so renames can be paired with their other side
+        let mut find_opts = git2::DiffFindOptions::new();
+        find_opts.renames(true);
+        let mut diff = if staged {
+            let head_tree = match repo.head() {
+                Ok(head) => Some(head.peel_to_tree()?),
+                Err(e) if e.code() == ErrorCode::UnbornBranch || e.code() == ErrorCode::NotFound => None,
+                Err(e) => return Err(e),
+            };
+            repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut opts))?
+        } else {
+            opts.include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+            find_opts.for_untracked(true);
+            repo.diff_index_to_workdir(None, Some(&mut opts))?
+        };
+        diff.find_similar(Some(&mut find_opts))?;
+        
+        let wanted = Path::new(file_path);
+        let index = diff.deltas().position(|delta| {
+            delta.new_file().path() == Some(wanted) || delta.old_file().path() == Some(wanted)
+        });
+        
+        let Some(index) = index else {
+            return Ok(FileDiff {
+                path: file_path.to_string(),
+                old_path: None,
+                status: FileChangeStatus::Unmodified,
+                is_binary: false,
+                lines_added: 0,
+                lines_deleted: 0,
+                hunks: Vec::new(),
+            });
+        };
+        
+        let delta = diff.get_delta(index).ok_or_else(|| git2::Error::from_str("diff delta disappeared"))?;
+        let path = delta.new_file().path().or(delta.old_file().path())
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.to_string());
+        let old_path = match delta.status() {
+            git2::Delta::Renamed | git2::Delta::Copied => delta.old_file().path().map(|p| p.to_string_lossy().to_string()),
+            _ => None,
+        };
+        let status = match delta.status() {
+            git2::Delta::Unmodified => FileChangeStatus::Unmodified,
+            git2::Delta::Added => FileChangeStatus::Added,
+            git2::Delta::Deleted => FileChangeStatus::Deleted,
+            git2::Delta::Renamed => FileChangeStatus::Renamed,
+            git2::Delta::Copied => FileChangeStatus::Copied,
+            git2::Delta::Typechange => FileChangeStatus::TypeChange,
+            git2::Delta::Untracked => FileChangeStatus::Untracked,
+            git2::Delta::Conflicted => FileChangeStatus::Conflicted,
+            _ => FileChangeStatus::Modified,
+        };
+        
+        let mut file_diff = FileDiff {
+            path,
+            old_path,
+            status,
+            is_binary: delta.flags().is_binary(),
+            lines_added: 0,
+            lines_deleted: 0,
+            hunks: Vec::new(),
+        };
+        
+        // Binary detection happens while the patch is generated, so check again afterwards
+        let Some(patch) = git2::Patch::from_diff(&diff, index)? else {
+            file_diff.is_binary = true;
+            return Ok(file_diff);
+        };
+        if patch.delta().flags().is_binary() {
+            file_diff.is_binary = true;
+            return Ok(file_diff);
+        }
+        
+        for hunk_idx in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_idx)?;
+            let mut diff_hunk = DiffHunk {
+                header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines: Vec::with_capacity(line_count),
+            };
+            
+            for line_idx in 0..line_count {
+                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                let kind = match line.origin() {
+                    ' ' => DiffLineKind::Context,
+                    '+' => DiffLineKind::Added,
+                    '-' => DiffLineKind::Removed,
+                    // "\ No newline at end of file" markers belong to the previous line
+                    _ => {
+                        if let Some(last) = diff_hunk.lines.last_mut() {
+                            last.missing_newline = true;
+                        }
+                        continue;
+                    }
+                };
+                
+                match kind {
+                    DiffLineKind::Added => file_diff.lines_added += 1,
+                    DiffLineKind::Removed => file_diff.lines_deleted += 1,
+                    DiffLineKind::Context => {},
+                }
+                
+                let content = String::from_utf8_lossy(line.content());
+                let content = content.strip_suffix('\n').unwrap_or(&content);
+                diff_hunk.lines.push(DiffLine {
+                    kind,
+                    old_lineno: line.old_lineno(),
+                    new_lineno: line.new_lineno(),
+                    content: content.strip_suffix('\r').unwrap_or(content).to_string(),
+                    missing_newline: false,
+                });
+            }
+            
+            file_diff.hunks.push(diff_hunk);
+        }
+        
+        Ok(file_diff)
+    }
+    
+    /// Stage files or untracked directories like `git add -A <paths>`, including deletions
+    pub fn stage_files(&self, repo_path: &Path, paths: &[String]) -> Result<(), git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let mut index = repo.index()?;
+        index.add_all(paths, git2::IndexAddOption::DISABLE_PATHSPEC_MATCH, None)?;
+        index.update_all(paths, None)?;
+        index.write()
+    }
+    
+    /// Reset the index entries of `paths` to HEAD, leaving the working tree alone
+    pub fn unstage_files(&self, repo_path: &Path, paths: &[String]) -> Result<(), git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let head = Self::head_commit(&repo)?;
+        match head {
+            Some(head) => repo.reset_default(Some(head.as_object()), paths),
+            None => {
+                // Nothing is committed yet, so unstaging removes the entries
+                let mut index = repo.index()?;
+                index.remove_all(paths, None)?;
+                index.write()
+            }
+        }
+    }
+    
+    /// Stage selected hunks of a tracked file's working-tree changes
+    pub fn stage_hunks(&self, repo_path: &Path, file_path: &str, hunks: &[HunkRange]) -> Result<(), git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let mut opts = Self::hunk_diff_options(file_path);
+        let diff = repo.diff_index_to_workdir(None, Some(&mut opts))?;
+        Self::apply_hunks(&repo, &diff, hunks, false)
+    }
+    
+    /// Unstage selected hunks of a file's staged changes. `hunks` are as reported by the
+    /// staged diff, i.e. HEAD on the old side and the index on the new side.
+    pub fn unstage_hunks(&self, repo_path: &Path, file_path: &str, hunks: &[HunkRange]) -> Result<(), git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let head_tree = match Self::head_commit(&repo)? {
+            Some(head) => Some(head.tree()?),
+            None => None,
+        };
+        
+        // The reversed diff takes the index back towards HEAD
+        let mut opts = Self::hunk_diff_options(file_path);
+        opts.reverse(true);
+        let diff = repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut opts))?;
+        Self::apply_hunks(&repo, &diff, hunks, true)
+    }
+    
+    /// Diff options matching `get_diff`, restricted to one file
+    fn hunk_diff_options(file_path: &str) -> git2::DiffOptions {
+        let mut opts = git2::DiffOptions::new();
+        opts.max_size(DIFF_MAX_FILE_BYTES)
+            .pathspec(file_path)
+            .disable_pathspec_match(true);
+        opts
+    }
+    
+    /// Apply the hunks of `diff` matching `hunks` to the index. When the diff is reversed the
+    /// requested ranges are flipped to match. Fails without changes if any hunk is missing.
+    fn apply_hunks(repo: &GitRepository, diff: &git2::Diff, hunks: &[HunkRange], reversed: bool) -> Result<(), git2::Error> {
+        let range = |hunk: &git2::DiffHunk| if reversed {
+            HunkRange { old_start: hunk.new_start(), old_lines: hunk.new_lines(), new_start: hunk.old_start(), new_lines: hunk.old_lines() }
+        } else {
+            HunkRange { old_start: hunk.old_start(), old_lines: hunk.old_lines(), new_start: hunk.new_start(), new_lines: hunk.new_lines() }
+        };
+        
+        let mut available = Vec::new();
+        diff.foreach(&mut |_, _| true, None, Some(&mut |_, hunk| {
+            available.push(range(&hunk));
+            true
+        }), None)?;
+        
+        if let Some(missing) = hunks.iter().find(|hunk| !available.contains(hunk)) {
+            return Err(git2::Error::from_str(&format!(
+                "hunk -{},{} +{},{} no longer matches the file, refresh the diff",
+                missing.old_start, missing.old_lines, missing.new_start, missing.new_lines
+            )));
+        }
+        
+        let mut apply_opts = git2::ApplyOptions::new();
+        apply_opts.hunk_callback(|hunk| hunk.is_some_and(|hunk| hunks.contains(&range(&hunk))));
+        repo.apply(diff, git2::ApplyLocation::Index, Some(&mut apply_opts))
+    }
+    
+    /// Discard working-tree changes to `paths`: tracked files go back to their staged
+    /// content (conflicted files to their conflict markers) and untracked files are deleted.
+    /// A directory may mix both. Staged changes are kept, and only files git reports as
+    /// untracked are ever deleted. Every existing path is first copied into `backup_dir`;
+    /// nothing is discarded if a copy fails. Returns the paths that were backed up.
+    pub fn discard_changes(&self, repo_path: &Path, paths: &[String], backup_dir: &Path) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let repo = GitRepository::open(repo_path)?;
+        let workdir = repo.workdir().ok_or("repository has no working tree")?.to_path_buf();
+        
+        for path in paths {
+            let relative = Path::new(path);
+            let inside = relative.components().next().is_some()
+                && relative.components().all(|c| matches!(c, Component::Normal(name) if name != ".git"));
+            if !inside {
+                return Err(format!("{} is not a path inside the repository", path).into());
+            }
+        }
+        
+        let mut backed_up = Vec::new();
+        for path in paths {
+            let source = workdir.join(path);
+            if source.symlink_metadata().is_ok() {
+                Self::copy_recursive(&source, &backup_dir.join(path))?;
+                backed_up.push(path.clone());
+            }
+        }
+        
+        let index = repo.index()?;
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.force().allow_conflicts(true).conflict_style_merge(true);
+        let mut has_tracked = false;
+        let mut untracked = Vec::new();
+        
+        for path in paths {
+            if Self::is_in_index(&index, Path::new(path)) {
+                checkout.path(path);
+                has_tracked = true;
+            }
+            
+            // Decide what to delete before the checkout touches anything
+            for file in Self::files_under(&workdir, Path::new(path))? {
+                let is_new = repo.status_file(&file).is_ok_and(|status| status == git2::Status::WT_NEW);
+                if is_new {
+                    untracked.push(file);
+                }
+            }
+        }
+        
+        if has_tracked {
+            repo.checkout_index(None, Some(&mut checkout))?;
+        }
+        
+        for file in &untracked {
+            std::fs::remove_file(workdir.join(file))?;
+        }
+        for path in paths {
+            Self::remove_empty_dirs(&workdir.join(path))?;
+        }
+        
+        Ok(backed_up)
+    }
+    
+    /// Check if `path` is an index entry at any stage, or a directory containing one
+    fn is_in_index(index: &git2::Index, path: &Path) -> bool {
+        if (0..=3).any(|stage| index.get_path(path, stage).is_some()) {
+            return true;
+        }
+        
+        let prefix = format!("{}/", path.to_string_lossy().trim_end_matches('/'));
+        index.iter().any(|entry| entry.path.starts_with(prefix.as_bytes()))
+    }
+    
+    /// Files (and symlinks) at or below `path`, relative to the working tree. Symlinked
+    /// directories aren't followed and nested `.git` directories are left alone.
+    fn files_under(workdir: &Path, path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        let metadata = match workdir.join(path).symlink_metadata() {
+            Ok(metadata) => metadata,
+            Err(_) => return Ok(Vec::new()),
+        };
+        if !metadata.is_dir() {
+            return Ok(vec![path.to_path_buf()]);
+        }
+        
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(workdir.join(path))? {
+            let name = entry?.file_name();
+            if name != ".git" {
+                files.extend(Self::files_under(workdir, &path.join(name))?);
+            }
+        }
+        Ok(files)
+    }
+    
+    /// Remove `dir` and its subdirectories if deleting untracked files left them empty
+    fn remove_empty_dirs(dir: &Path) -> std::io::Result<()> {
+        if !dir.symlink_metadata().is_ok_and(|metadata| metadata.is_dir()) {
+            return Ok(());
+        }
+        
+        for entry in std::fs::read_dir(dir)? {
+            Self::remove_empty_dirs(&entry?.path())?;
+        }
+        if std::fs::read_dir(dir)?.next().is_none() {
+            std::fs::remove_dir(dir)?;
+        }
+        Ok(())
+    }
+    
+    fn copy_recursive(source: &Path, target: &Path) -> std::io::Result<()> {
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        
+        if source.symlink_metadata()?.is_dir() {
+            std::fs::create_dir_all(target)?;
+            for entry in std::fs::read_dir(source)? {
+                let entry = entry?;
+                Self::copy_recursive(&entry.path(), &target.join(entry.file_name()))?;
+            }
+            Ok(())
+        } else {
+            std::fs::copy(source, target).map(|_| ())
+        }
+    }
+    
+    /// Commit the staged changes with the git CLI, so hooks, signing and the author
+    /// identity from git config all apply as they would in a terminal
+    pub async fn create_commit(&self, repo_path: &Path, message: &str, timeout: Duration) -> Result<CommitResult, Box<dyn std::error::Error + Send + Sync>> {
+        if message.trim().is_empty() {
+            return Err("Commit message is empty".into());
+        }
+        
+        let hooks = {
+            let repo = GitRepository::open(repo_path)?;
+            if repo.signature().is_err() {
+                return Err("No commit identity configured, set user.name and user.email in git config".into());
+            }
+            Self::commit_hooks(&repo)
+        };
+        
+        let args = vec!["commit".to_string(), "-m".to_string(), message.to_string()];
+        let result = self.execute_command(repo_path, &args, timeout).await?;
+        let output: Vec<&str> = [result.stdout.as_str(), result.stderr.as_str()].into_iter().filter(|s| !s.is_empty()).collect();
+        let output = output.join("\n");
+        
+        if !result.success {
+            let lower = output.to_lowercase();
+            let error = if result.timed_out {
+                format!("Commit timed out after {}s, a hook may be waiting for input", timeout.as_secs())
+            } else if lower.contains("failed to sign") || lower.contains("gpg failed") || lower.contains("signing failed") {
+                format!("Commit signing failed: {}", output)
+            } else if ["nothing to commit", "nothing added to commit", "no changes added to commit"].iter().any(|m| lower.contains(m)) {
+                "Nothing to commit, stage changes first".to_string()
+            } else if !hooks.is_empty() {
+                format!("Commit rejected by {} hook: {}", hooks.join("/"), output)
+            } else {
+                format!("Commit failed: {}", output)
+            };
+            return Err(error.into());
+        }
+        
+        let repo = GitRepository::open(repo_path)?;
+        let head = repo.head()?;
+        let commit = head.peel_to_commit()?;
+        
+        Ok(CommitResult {
+            sha: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+            branch: head.is_branch().then(|| head.shorthand().map(|s| s.to_string())).flatten(),
+            output,
+        })
+    }
+    
+    /// Installed hooks that can reject a commit
+    fn commit_hooks(repo: &GitRepository) -> Vec<&'static str> {
+        let hooks_dir = match repo.config().and_then(|config| config.get_path("core.hooksPath")) {
+            Ok(path) if path.is_absolute() => path,
+            Ok(path) => repo.workdir().unwrap_or(repo.path()).join(path),
+            Err(_) => repo.path().join("hooks"),
+        };
+        
+        ["pre-commit", "prepare-commit-msg", "commit-msg"].into_iter()
+            .filter(|hook| hooks_dir.join(hook).is_file())
+            .collect()
+    }
+    
+    /// Number of stash entries (0 when there is no stash)
+    pub fn get_stash_count(&self, repo_path: &Path) -> usize {
+        GitRepository::open(repo_path).ok()
+            .and_then(|repo| repo.reflog("refs/stash").ok().map(|reflog| reflog.len()))
+            .unwrap_or(0)
+    }
+    
+    /// List stashes newest first. Stashes are read from the `refs/stash` reflog,
+    /// whose entry order matches `stash@{n}`.
+    pub fn list_stashes(&self, repo_path: &Path) -> Result<Vec<StashInfo>, git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let reflog = match repo.reflog("refs/stash") {
+            Ok(reflog) => reflog,
+            Err(e) if e.code() == ErrorCode::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        
+        let mut stashes = Vec::new();
+        for (index, entry) in reflog.iter().enumerate() {
+            let message = entry.message().unwrap_or("").to_string();
+            let commit = repo.find_commit(entry.id_new())?;
+            
+            stashes.push(StashInfo {
+                repo_path: repo_path.to_string_lossy().to_string(),
+                index,
+                sha: entry.id_new().to_string(),
+                branch: Self::stash_branch(&message),
+                message,
+                date: DateTime::from_timestamp(commit.time().seconds(), 0),
+                file_count: Self::stash_file_count(&repo, &commit)?,
+            });
+        }
+        
+        Ok(stashes)
+    }
+    
+    /// Apply a stash, keeping it in the stash list
+    pub fn apply_stash(&self, repo_path: &Path, index: usize, expected_sha: &str) -> Result<(), git2::Error> {
+        let mut repo = GitRepository::open(repo_path)?;
+        Self::check_stash(&repo, index, expected_sha)?;
+        repo.stash_apply(index, None)
+    }
+    
+    /// Drop a stash. The stash commit stays reachable by its SHA until garbage collection.
+    pub fn drop_stash(&self, repo_path: &Path, index: usize, expected_sha: &str) -> Result<(), git2::Error> {
+        let mut repo = GitRepository::open(repo_path)?;
+        Self::check_stash(&repo, index, expected_sha)?;
+        repo.stash_drop(index)
+    }
+    
+    /// Unified diff of a stash against the commit it was created on, including stashed untracked files
+    pub fn get_stash_diff(&self, repo_path: &Path, index: usize, expected_sha: &str) -> Result<String, git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        Self::check_stash(&repo, index, expected_sha)?;
+        let commit = repo.find_commit(Oid::from_str(expected_sha)?)?;
+        
+        let mut patch = String::new();
+        for diff in Self::stash_diffs(&repo, &commit)? {
+            diff.print(git2::DiffFormat::Patch, |_, _, line| {
+                if matches!(line.origin(), '+' | '-' | ' ') {
+                    patch.push(line.origin());
+                }
+                patch.push_str(&String::from_utf8_lossy(line.content()));
+                true
+            })?;
+        }
+        
+        Ok(patch)
+    }
+    
+    /// Make sure `stash@{index}` is still the stash the caller saw
+    fn check_stash(repo: &GitRepository, index: usize, expected_sha: &str) -> Result<(), git2::Error> {
+        let current = repo.reflog("refs/stash")?
+            .get(index)
+            .map(|entry| entry.id_new().to_string());
+        
+        if current.as_deref() == Some(expected_sha) {
+            Ok(())
+        } else {
+            Err(git2::Error::from_str(&format!("stash@{{{}}} is no longer {}", index, expected_sha)))
+        }
+    }
+    
+    /// "WIP on main: abc123 msg" / "On main: msg" -> "main"
+    fn stash_branch(message: &str) -> Option<String> {
+        let rest = message.strip_prefix("WIP on ").or_else(|| message.strip_prefix("On "))?;
+        let (branch, _) = rest.split_once(':')?;
+        (branch != "(no branch)").then(|| branch.to_string())
+    }
+    
+    /// Diffs that make up a stash: tracked changes against the base commit, plus the
+    /// untracked-files commit (third parent) when the stash was made with `-u`
+    fn stash_diffs<'r>(repo: &'r GitRepository, stash: &git2::Commit) -> Result<Vec<git2::Diff<'r>>, git2::Error> {
+        let base_tree = stash.parent(0)?.tree()?;
+        let mut diffs = vec![repo.diff_tree_to_tree(Some(&base_tree), Some(&stash.tree()?), None)?];
+        
+        if let Ok(untracked) = stash.parent(2) {
+            diffs.push(repo.diff_tree_to_tree(None, Some(&untracked.tree()?), None)?);
+        }
+        
+        Ok(diffs)
+    }
+    
+    fn stash_file_count(repo: &GitRepository, stash: &git2::Commit) -> Result<usize, git2::Error> {
+        Ok(Self::stash_diffs(repo, stash)?.iter().map(|diff| diff.deltas().len()).sum())
+    }
+    
+    /// Get the SHA of the commit HEAD points to (None for unborn or unreadable HEADs)
+    pub fn get_head_sha(&self, repo_path: &Path) -> Option<String> {
+        let repo = GitRepository::open(repo_path).ok()?;
+        let head = repo.head().ok()?;
+        head.target().map(|oid| oid.to_string())
+    }
+    
+    /// Get the last modification time of the repository index (`.git/index`)
+    pub fn get_index_modified(&self, repo_path: &Path) -> Option<DateTime<Utc>> {
+        let repo = GitRepository::open(repo_path).ok()?;
+        let metadata = std::fs::metadata(repo.path().join("index")).ok()?;
+        metadata.modified().ok().map(DateTime::<Utc>::from)
+    }
+    
+    /// Digest of the working tree's uncommitted changes: each changed path with its status,
+    /// size and modified time, so further edits to an already-modified file change it too.
+    /// None when the working tree is clean.
+    pub fn get_worktree_signature(&self, repo_path: &Path) -> Option<String> {
+        let repo = GitRepository::open(repo_path).ok()?;
+        let workdir = repo.workdir()?.to_path_buf();
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true);
+        let statuses = repo.statuses(Some(&mut opts)).ok()?;
+        if statuses.is_empty() {
+            return None;
+        }
+        
+        let mut hasher = DefaultHasher::new();
+        for status in statuses.iter() {
+            let path = status.path().unwrap_or_default();
+            path.hash(&mut hasher);
+            status.status().bits().hash(&mut hasher);
+            if let Ok(metadata) = std::fs::symlink_metadata(workdir.join(path)) {
+                metadata.len().hash(&mut hasher);
+                metadata.modified().ok().hash(&mut hasher);
+            }
+        }
+        Some(format!("{:016x}", hasher.finish()))
+    }
+    
+    /// Get the committer time of the commit HEAD points to
+    pub fn get_head_commit_time(&self, repo_path: &Path) -> Option<DateTime<Utc>> {
+        let repo = GitRepository::open(repo_path).ok()?;
+        let commit = repo.head().ok()?.peel_to_commit().ok()?;
+        DateTime::from_timestamp(commit.time().seconds(), 0)
+    }
+    
+    /// Count commits reachable from HEAD, stopping at `COMMIT_COUNT_LIMIT`, and whether
+    /// there were more. Returns None for repositories without commits.
+    pub fn count_commits(&self, repo_path: &Path) -> Option<(u32, bool)> {
+        let repo = GitRepository::open(repo_path).ok()?;
+        let mut revwalk = repo.revwalk().ok()?;
+        revwalk.push_head().ok()?;
+        
+        let count = revwalk
+            .filter_map(|oid| oid.ok())
+            .take(COMMIT_COUNT_LIMIT + 1)
+            .count();
+        Some((count.min(COMMIT_COUNT_LIMIT) as u32, count > COMMIT_COUNT_LIMIT))
+    }
+    
+    /// Summarize commits in the last `days` days. The revwalk runs newest-first and stops at
+    /// the first commit older than the window, so cost depends on recent activity rather
+    /// than total history. Merge commits are counted but not diffed.
+    pub fn get_activity(&self, repo_path: &Path, days: u32) -> Result<RepoActivity, git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let mut activity = RepoActivity {
+            window_days: days,
+            ..Default::default()
+        };
+        
+        let head_commit = match Self::head_commit(&repo)? {
+            Some(commit) => commit,
+            None => return Ok(activity),
+        };
+        activity.last_commit_date = DateTime::from_timestamp(head_commit.time().seconds(), 0);
+        activity.total_commits = self.count_commits(repo_path).map(|(count, _)| count).unwrap_or(0);
+        
+        let now = Utc::now();
+        let week_start = (now - ChronoDuration::days(7)).timestamp();
+        let window_start = (now - ChronoDuration::days(days as i64)).timestamp();
+        
+        let mut authors = BTreeSet::new();
+        let mut files_changed = HashSet::new();
+        
+        activity.truncated = Self::walk_commits_since(&repo, window_start.min(week_start), |commit| {
+            let commit_time = commit.time().seconds();
+            if commit_time >= week_start {
+                activity.commits_last_week += 1;
+            }
+            if commit_time < window_start {
+                return Ok(());
+            }
+            
+            activity.commits_last_month += 1;
+            if let Some(name) = commit.author().name() {
+                authors.insert(name.to_string());
+            }
+            
+            if let Some(diff) = Self::commit_diff(&repo, commit)? {
+                let stats = diff.stats()?;
+                activity.lines_added_last_month += stats.insertions();
+                activity.lines_deleted_last_month += stats.deletions();
+                
+                for delta in diff.deltas() {
+                    if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+                        files_changed.insert(path.to_path_buf());
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        
+        activity.authors_last_month = authors.into_iter().collect();
+        activity.files_changed_last_month = files_changed.len();
+        Ok(activity)
+    }
+    
+    /// Per-author commits, line changes and active days in the last `days` days.
+    /// Identities are resolved through the repository's mailmap (`.mailmap`, `mailmap.file`)
+    /// and grouped by email. The flag is set when the walk stopped at `ACTIVITY_COMMIT_LIMIT`.
+    pub fn get_author_stats(&self, repo_path: &Path, days: u32) -> Result<(Vec<AuthorCommitStats>, bool), git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        if Self::head_commit(&repo)?.is_none() {
+            return Ok((Vec::new(), false));
+        }
+        
+        let mailmap = repo.mailmap()?;
+        let since = (Utc::now() - ChronoDuration::days(days as i64)).timestamp();
+        let mut authors: HashMap<String, AuthorCommitStats> = HashMap::new();
+        
+        let truncated = Self::walk_commits_since(&repo, since, |commit| {
+            let author = commit.author_with_mailmap(&mailmap).unwrap_or_else(|_| commit.author().to_owned());
+            let name = author.name().unwrap_or("Unknown").to_string();
+            let email = author.email().unwrap_or("").to_lowercase();
+            let key = if email.is_empty() { name.to_lowercase() } else { email.clone() };
+            
+            // Newest commits come first, so the stored name is the author's most recent one
+            let stats = authors.entry(key).or_insert_with(|| AuthorCommitStats {
+                name,
+                email,
+                ..Default::default()
+            });
+            stats.commits += 1;
+            
+            // Active days use the author's own timezone, like `git log --date=local` on their machine
+            let when = author.when();
+            if let Some(authored) = DateTime::from_timestamp(when.seconds() + when.offset_minutes() as i64 * 60, 0) {
+                stats.active_days.insert(authored.date_naive());
+            }
+            
+            let commit_date = DateTime::from_timestamp(commit.time().seconds(), 0);
+            if stats.last_commit_date.is_none() {
+                stats.last_commit_date = commit_date;
+            }
+            
+            if let Some(diff) = Self::commit_diff(&repo, commit)? {
+                let diff_stats = diff.stats()?;
+                stats.lines_added += diff_stats.insertions();
+                stats.lines_deleted += diff_stats.deletions();
+            }
+            Ok(())
+        })?;
+        
+        Ok((authors.into_values().collect(), truncated))
+    }
+    
+    /// Commits per day (author date, in the author's timezone) from `since` until today.
+    /// The flag is set when the walk stopped at `ACTIVITY_COMMIT_LIMIT`.
+    pub fn get_daily_commit_counts(&self, repo_path: &Path, since: NaiveDate) -> Result<(BTreeMap<NaiveDate, u32>, bool), git2::Error> {
+        let repo = GitRepository::open(repo_path)?;
+        let mut daily_commits = BTreeMap::new();
+        if Self::head_commit(&repo)?.is_none() {
+            return Ok((daily_commits, false));
+        }
+        
+        // Walk a day further back so commits from timezones ahead of UTC aren't cut off
+        let walk_since = since.and_hms_opt(0, 0, 0)
+            .map(|midnight| (midnight - ChronoDuration::days(1)).and_utc().timestamp())
+            .unwrap_or(0);
+        
+        let truncated = Self::walk_commits_since(&repo, walk_since, |commit| {
+            let when = commit.author().when();
+            if let Some(authored) = DateTime::from_timestamp(when.seconds() + when.offset_minutes() as i64 * 60, 0) {
+                let date = authored.date_naive();
+                if date >= since {
+                    *daily_commits.entry(date).or_insert(0) += 1;
+                }
+            }
+            Ok(())
+        })?;
+        
+        Ok((daily_commits, truncated))
+    }
+    
+    /// The commit HEAD points to, or None for unborn branches
+    fn head_commit(repo: &GitRepository) -> Result<Option<git2::Commit<'_>>, git2::Error> {
+        match repo.head() {
+            Ok(head) => head.peel_to_commit().map(Some),
+            Err(e) if e.code() == ErrorCode::UnbornBranch || e.code() == ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Visit commits reachable from HEAD newest-first, stopping at the first commit older than
+    /// `since` (unix seconds). Returns true when the walk stopped at `ACTIVITY_COMMIT_LIMIT`.
+    fn walk_commits_since<F>(repo: &GitRepository, since: i64, mut visit: F) -> Result<bool, git2::Error>
+    where
+        F: FnMut(&git2::Commit) -> Result<(), git2::Error>,
+    {
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TIME)?;
+        revwalk.push_head()?;
+        
+        for (walked, oid) in revwalk.enumerate() {
+            let commit = repo.find_commit(oid?)?;
+            if commit.time().seconds() < since {
+                return Ok(false);
+            }
+            if walked >= ACTIVITY_COMMIT_LIMIT {
+                return Ok(true);
+            }
+            visit(&commit)?;
+        }
+        
+        Ok(false)
+    }
+    
+    /// Diff a commit against its parent (the empty tree for root commits).
+    /// Merge commits return None so their changes aren't counted twice.
+    fn commit_diff<'r>(repo: &'r GitRepository, commit: &git2::Commit) -> Result<Option<git2::Diff<'r>>, git2::Error> {
+        if commit.parent_count() > 1 {
+            return Ok(None);
+        }
+        
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None).map(Some)
+    }
+    
+    /// Fetch all remotes, pruning deleted remote branches
+    pub async fn fetch(&self, repo_path: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.run_git(repo_path, &["fetch", "--all", "--prune"]).await
+    }
+    
+    /// Pull the current branch, refusing anything that isn't a fast-forward
+    pub async fn pull_fast_forward(&self, repo_path: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.run_git(repo_path, &["pull", "--ff-only"]).await
+    }
+    
+    /// Push the current branch to its upstream
+    pub async fn push(&self, repo_path: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.run_git(repo_path, &["push"]).await
+    }
+    
+    /// Commit staged changes, optionally staging everything (including untracked files) first
+    pub async fn commit(&self, repo_path: &Path, message: &str, stage_all: bool) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        if stage_all {
+            self.run_git(repo_path, &["add", "--all"]).await?;
+        }
+        self.run_git(repo_path, &["commit", "-m", message]).await
+    }
+    
+    /// Initialize and update submodules, including nested ones
+    pub async fn submodule_update(&self, repo_path: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.run_git(repo_path, &["submodule", "update", "--init", "--recursive"]).await
+    }
+    
+    /// Short status including the branch/upstream line
+    pub async fn status_summary(&self, repo_path: &Path) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.run_git(repo_path, &["status", "--short", "--branch"]).await
+    }
+    
+    /// Run user-supplied git arguments in the repository, killing git after `timeout`.
+    /// Callers are expected to have checked the arguments against a `GitCommandPolicy`.
+    pub async fn execute_command(&self, repo_path: &Path, args: &[String], timeout: Duration) -> Result<GitCommandResult, Box<dyn std::error::Error + Send + Sync>> {
+        let started = Instant::now();
+        let child = Self::git_command(repo_path, args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        
+        // Dropping the wait future on timeout kills the child
+        let (output, timed_out) = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+            Ok(output) => (Some(output?), false),
+            Err(_) => (None, true),
+        };
+        
+        let mut result = GitCommandResult {
+            command: args.to_vec(),
+            success: false,
+            exit_code: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            timed_out,
+            elapsed_ms: started.elapsed().as_millis() as u64,
+        };
+        
+        match output {
+            Some(output) => {
+                result.success = output.status.success();
+                result.exit_code = output.status.code();
+                result.stdout = String::from_utf8_lossy(&output.stdout).trim_end().to_string();
+                result.stderr = String::from_utf8_lossy(&output.stderr).trim_end().to_string();
+            },
+            None => result.stderr = format!("git {} timed out after {}s", args.join(" "), timeout.as_secs()),
+        }
+        
+        Ok(result)
+    }
+    
+    /// Build a git CLI invocation isolated from the terminal: credential prompts, SSH host key
+    /// and passphrase prompts, and editors are disabled so commands fail instead of hanging the
+    /// app waiting for input.
+    fn git_command<I, S>(repo_path: &Path, args: I) -> Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = Command::new("git");
+        command
+            .args(args)
+            .current_dir(repo_path)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("GCM_INTERACTIVE", "never")
+            .env("GIT_EDITOR", "true")
+            .stdin(Stdio::null());
+        if !Self::has_ssh_command(repo_path) {
+            command.env("GIT_SSH_COMMAND", "ssh -o BatchMode=yes");
+        }
+        command
+    }
+    
+    /// Whether the user chose an SSH command, which `GIT_SSH_COMMAND` would override
+    fn has_ssh_command(repo_path: &Path) -> bool {
+        std::env::var_os("GIT_SSH_COMMAND").is_some()
+            || std::env::var_os("GIT_SSH").is_some()
+            || GitRepository::open(repo_path)
+                .and_then(|repo| repo.config())
+                .and_then(|config| config.get_string("core.sshCommand"))
+                .is_ok()
+    }
+    
+    /// Run the git CLI in the repository. The CLI (rather than git2) is used for network and
+    /// commit operations so the user's credential helpers, SSH agent and hooks apply.
+    /// Like user commands, git is killed after `GIT_CLI_TIMEOUT` so a stalled remote can't hang.
+    async fn run_git(&self, repo_path: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let result = self.execute_command(repo_path, &args, GIT_CLI_TIMEOUT).await?;
+        
+        let stdout = result.stdout.trim().to_string();
+        let stderr = result.stderr.trim().to_string();
+        
+        if result.success {
+            // fetch/push/pull report progress on stderr, so keep both streams
+            let combined: Vec<String> = [stdout, stderr].into_iter().filter(|s| !s.is_empty()).collect();
+            Ok(combined.join("\n"))
+        } else if stderr.is_empty() {
+            Err(format!("git {} failed: {}", args.join(" "), stdout).into())
+        } else {
+            Err(stderr.into())
+        }
+    }
+    
     pub fn is_git_repository(&self, path: &Path) -> bool {
         GitRepository::open(path).is_ok()
     }
@@ -193,4 +1536,104 @@ impl GitAdapter {
             Err(e) => Err(e),
         }
     }
-}
\ No newline at end of file
+}
+
+/// A repository's default branch with its local and `origin` tips
+struct DefaultBranch {
+    name: String,
+    local: Option<Oid>,
+    remote: Option<Oid>,
+}
+
+impl DefaultBranch {
+    fn tips(&self) -> impl Iterator<Item = Oid> {
+        self.local.into_iter().chain(self.remote)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::Command as StdCommand;
+
+    /// Fresh repository with one commit, in a per-test temp directory
+    fn test_repo(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("repo-manager-git-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q", "-b", "main"]);
+        git(&dir, &["config", "user.email", "test@example.com"]);
+        git(&dir, &["config", "user.name", "Test"]);
+        fs::write(dir.join("README.md"), "readme\n").unwrap();
+        git(&dir, &["add", "."]);
+        git(&dir, &["commit", "-qm", "initial"]);
+        dir
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> bool {
+        StdCommand::new("git").args(args).current_dir(dir).output().unwrap().status.success()
+    }
+
+    #[test]
+    fn discard_restores_conflicted_file() {
+        let repo = test_repo("discard-conflict");
+        fs::write(repo.join("file.txt"), "base\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-qm", "base"]);
+        git(&repo, &["checkout", "-qb", "other"]);
+        fs::write(repo.join("file.txt"), "theirs\n").unwrap();
+        git(&repo, &["commit", "-qam", "theirs"]);
+        git(&repo, &["checkout", "-q", "main"]);
+        fs::write(repo.join("file.txt"), "ours\n").unwrap();
+        git(&repo, &["commit", "-qam", "ours"]);
+        assert!(!git(&repo, &["merge", "-q", "other"]));
+        fs::write(repo.join("file.txt"), "half-resolved\n").unwrap();
+
+        let backup = repo.join("../discard-conflict-backup");
+        GitAdapter::new().discard_changes(&repo, &["file.txt".to_string()], &backup).unwrap();
+
+        let content = fs::read_to_string(repo.join("file.txt")).unwrap();
+        assert!(content.contains("<<<<<<<") && content.contains("ours") && content.contains("theirs"));
+        assert_eq!(fs::read_to_string(backup.join("file.txt")).unwrap(), "half-resolved\n");
+        let _ = fs::remove_dir_all(&repo);
+        let _ = fs::remove_dir_all(&backup);
+    }
+
+    #[test]
+    fn discard_directory_keeps_tracked_files() {
+        let repo = test_repo("discard-mixed");
+        fs::create_dir_all(repo.join("src/nested")).unwrap();
+        fs::write(repo.join("src/lib.rs"), "tracked\n").unwrap();
+        fs::write(repo.join("src/ignored.log"), "ignored\n").unwrap();
+        fs::write(repo.join(".gitignore"), "*.log\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-qm", "src"]);
+        fs::write(repo.join("src/lib.rs"), "modified\n").unwrap();
+        fs::write(repo.join("src/new.rs"), "untracked\n").unwrap();
+        fs::write(repo.join("src/nested/new.rs"), "untracked\n").unwrap();
+
+        let backup = repo.join("../discard-mixed-backup");
+        GitAdapter::new().discard_changes(&repo, &["src".to_string()], &backup).unwrap();
+
+        assert_eq!(fs::read_to_string(repo.join("src/lib.rs")).unwrap(), "tracked\n");
+        assert!(repo.join("src/ignored.log").exists());
+        assert!(!repo.join("src/new.rs").exists());
+        assert!(!repo.join("src/nested").exists());
+        assert!(backup.join("src/nested/new.rs").exists());
+        let _ = fs::remove_dir_all(&repo);
+        let _ = fs::remove_dir_all(&backup);
+    }
+
+    #[test]
+    fn discard_rejects_paths_outside_the_working_tree() {
+        let repo = test_repo("discard-reject");
+        let backup = repo.join("../discard-reject-backup");
+        for path in ["", ".", "./", ".git", ".git/config", "src/../..", "/etc/passwd"] {
+            assert!(GitAdapter::new().discard_changes(&repo, &[path.to_string()], &backup).is_err(), "{:?} was accepted", path);
+        }
+        assert!(repo.join(".git").exists() && repo.join("README.md").exists());
+        let _ = fs::remove_dir_all(&repo);
+    }
+}
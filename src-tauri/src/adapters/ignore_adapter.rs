@@ -1,58 +1,283 @@
 // Gitignore and file filtering adapter
-use ignore::WalkBuilder;
-use std::path::Path;
+use crate::models::{ScanRootRules, ScanRules};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::{Match, WalkBuilder};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-pub struct IgnoreAdapter;
+/// How deep `find_directories` follows a `**` pattern
+const MAX_GLOB_SEARCH_DEPTH: usize = 6;
+
+/// Applies the user's `ScanRules` to the directory walk
+pub struct IgnoreAdapter {
+    rules: Arc<CompiledScanRules>,
+}
+
+impl Default for IgnoreAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl IgnoreAdapter {
+    /// Adapter using the default scan rules
     pub fn new() -> Self {
-        Self
+        Self::with_rules(&ScanRules::default())
+    }
+
+    /// Adapter using `rules`. Invalid patterns are logged and ignored; use `validate_rules`
+    /// to reject them up front.
+    pub fn with_rules(rules: &ScanRules) -> Self {
+        Self {
+            rules: Arc::new(CompiledScanRules::compile(rules, |e| println!("SCAN RULES: Ignoring invalid pattern: {}", e))),
+        }
+    }
+
+    /// Check that every pattern in `rules` parses
+    pub fn validate_rules(rules: &ScanRules) -> Result<(), String> {
+        let mut errors = Vec::new();
+        CompiledScanRules::compile(rules, |e| errors.push(e));
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Invalid scan rule patterns: {}", errors.join("; ")))
+        }
     }
 
-    /// Create a gitignore-aware walker
+    /// Create a gitignore-aware walker that doesn't descend into excluded directories or
+    /// Git repositories; a repository (or bare repository) is yielded, its contents
+    /// (including submodules and nested worktrees) are not.
+    ///
+    /// Repository roots are recorded as they're yielded, so an entry is rejected by looking
+    /// its parent up in that set rather than probing the filesystem. Only directories are
+    /// checked for a `.git`, once each.
     pub fn create_walker(&self, base_path: &Path, max_depth: Option<usize>) -> ignore::Walk {
+        let rules = Arc::clone(&self.rules);
+        let repo_roots = Mutex::new(RepoRootSet::default());
+
         WalkBuilder::new(base_path)
             .max_depth(max_depth)
             .hidden(false) // We'll handle hidden files ourselves
             .git_ignore(true) // Respect .gitignore files
             .git_global(true) // Respect global .gitignore
             .git_exclude(true) // Respect .git/info/exclude
+            .filter_entry(move |entry| {
+                // The scan root itself is always walked, whatever its name
+                if entry.depth() == 0 {
+                    return true;
+                }
+
+                let Ok(mut repo_roots) = repo_roots.lock() else {
+                    return true;
+                };
+                if entry.path().parent().is_some_and(|parent| repo_roots.contains(parent)) {
+                    return false;
+                }
+
+                if !entry.file_type().is_some_and(|file_type| file_type.is_dir()) {
+                    return true;
+                }
+                if rules.is_excluded(entry.path()) {
+                    return false;
+                }
+
+                if is_repository_root(entry.path()) {
+                    repo_roots.insert(entry.path().to_path_buf());
+                }
+                true
+            })
             .build()
     }
 
-    /// Check if directory should be skipped based on name patterns
-    pub fn should_skip_directory(&self, dir_path: &Path) -> bool {
-        let dir_name = dir_path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("");
+    /// Directories below `base_path` matching one of `patterns` and none of `exclude`.
+    /// Patterns are relative to `base_path` and use .gitignore glob syntax (`*` stays within
+    /// a path component, `**` crosses them); patterns leaving `base_path` match nothing.
+    /// The search skips excluded directories and doesn't enter nested repositories.
+    pub fn find_directories(&self, base_path: &Path, patterns: &[String], exclude: &[String]) -> Vec<PathBuf> {
+        let anchored = |pattern: &String| {
+            let pattern = pattern.trim().trim_start_matches("./").trim_end_matches('/');
+            let escapes = pattern.is_empty() || pattern == "." || pattern.split('/').any(|part| part == "..")
+                || Path::new(pattern).is_absolute();
+            (!escapes).then(|| format!("/{}", pattern))
+        };
+        let patterns: Vec<String> = patterns.iter().filter_map(anchored).collect();
+        if patterns.is_empty() {
+            return Vec::new();
+        }
 
-        // Skip hidden directories
-        if dir_name.starts_with('.') {
-            return true;
+        // Excludes are added last so they win over the member patterns
+        let mut builder = OverrideBuilder::new(base_path);
+        let globs = patterns.iter().cloned().chain(exclude.iter().filter_map(anchored).map(|pattern| format!("!{}", pattern)));
+        for glob in globs {
+            if let Err(e) = builder.add(&glob) {
+                println!("IGNORE: Ignoring invalid glob: {} ({})", glob, e);
+            }
         }
+        let matcher = match builder.build() {
+            Ok(matcher) => matcher,
+            Err(e) => {
+                println!("IGNORE: Invalid globs: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let max_depth = patterns.iter()
+            .map(|pattern| if pattern.contains("**") { MAX_GLOB_SEARCH_DEPTH } else { pattern.matches('/').count() })
+            .max();
+
+        self.create_walker(base_path, max_depth)
+            .filter_map(Result::ok)
+            .filter(|entry| entry.depth() > 0 && entry.file_type().is_some_and(|file_type| file_type.is_dir()))
+            .filter(|entry| matcher.matched(entry.path(), true).is_whitelist())
+            .map(|entry| entry.into_path())
+            .collect()
+    }
 
-        // Skip npm scoped packages (directories starting with @)
-        if dir_name.starts_with('@') {
+    /// Check if a directory should not be reported as a project
+    pub fn should_skip_directory(&self, dir_path: &Path) -> bool {
+        if self.rules.is_excluded(dir_path) {
             return true;
         }
 
-        // Skip common build/dependency directories that are never projects
-        let skip_dirs = [
-            // Package managers & dependencies
-            "node_modules", "vendor", "target", "dist", "build", "out",
-            // Source subdirectories (not root projects)
-            "src", "lib", "libs", "components", "utils", "helpers",
-            // Test directories
-            "tests", "test", "__tests__", "spec", "specs",
-            // Cache/temp
-            "cache", ".cache", "tmp", "temp", "logs",
-            // OS specific
-            "System Volume Information", "$RECYCLE.BIN", "Thumbs.db", 
-            ".Trash", ".DS_Store",
-        ];
-
-        // Case-insensitive matching
-        let dir_name_lower = dir_name.to_lowercase();
-        skip_dirs.iter().any(|&skip| dir_name_lower == skip.to_lowercase())
-    }
-}
\ No newline at end of file
+        // Repositories named like source folders (`lib`, `build`) or dotfile repos still count
+        self.rules.is_not_project(dir_path) && !is_repository_root(dir_path)
+    }
+}
+
+/// Check if a directory is a repository's working tree (`.git` directory or gitdir file),
+/// or a Git directory itself (bare repositories, `--separate-git-dir` layouts)
+fn is_repository_root(dir_path: &Path) -> bool {
+    dir_path.join(".git").exists()
+        || (dir_path.join("HEAD").is_file() && dir_path.join("objects").is_dir() && dir_path.join("refs").is_dir())
+}
+
+/// Set of repository roots for rejecting paths inside them without touching the filesystem
+#[derive(Debug, Clone, Default)]
+pub struct RepoRootSet {
+    roots: HashSet<PathBuf>,
+}
+
+impl RepoRootSet {
+    pub fn insert(&mut self, root: PathBuf) {
+        self.roots.insert(root);
+    }
+
+    pub fn contains(&self, root: &Path) -> bool {
+        self.roots.contains(root)
+    }
+
+    /// Check if `path` lies below one of the roots (a root itself doesn't count)
+    pub fn contains_ancestor_of(&self, path: &Path) -> bool {
+        path.ancestors().skip(1).any(|ancestor| self.roots.contains(ancestor))
+    }
+}
+
+impl FromIterator<PathBuf> for RepoRootSet {
+    fn from_iter<I: IntoIterator<Item = PathBuf>>(iter: I) -> Self {
+        Self { roots: iter.into_iter().collect() }
+    }
+}
+
+struct CompiledScanRules {
+    global: RuleSet,
+    /// Per-root rules, longest root first so the most specific root wins
+    roots: Vec<(PathBuf, RuleSet)>,
+    never_skip_names: Vec<String>,
+    never_skip_paths: Vec<PathBuf>,
+}
+
+struct RuleSet {
+    exclude: Gitignore,
+    not_projects: Gitignore,
+}
+
+impl CompiledScanRules {
+    fn compile(rules: &ScanRules, mut on_error: impl FnMut(String)) -> Self {
+        let global = RuleSet::compile(Path::new("/"), &rules.exclude, &rules.not_projects, &rules.include, &mut on_error);
+
+        let mut roots: Vec<(PathBuf, RuleSet)> = rules.root_overrides.iter()
+            .map(|ScanRootRules { root, exclude, not_projects, include, .. }| {
+                (root.clone(), RuleSet::compile(root, exclude, not_projects, include, &mut on_error))
+            })
+            .collect();
+        roots.sort_by_key(|(root, _)| std::cmp::Reverse(root.components().count()));
+
+        let (never_skip_paths, never_skip_names): (Vec<&String>, Vec<&String>) = rules.never_skip.iter()
+            .partition(|entry| Path::new(entry).is_absolute());
+
+        Self {
+            global,
+            roots,
+            never_skip_names: never_skip_names.into_iter().map(|name| name.to_lowercase()).collect(),
+            never_skip_paths: never_skip_paths.into_iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    fn is_excluded(&self, dir_path: &Path) -> bool {
+        self.matches(dir_path, |rule_set| &rule_set.exclude)
+    }
+
+    fn is_not_project(&self, dir_path: &Path) -> bool {
+        self.matches(dir_path, |rule_set| &rule_set.not_projects)
+    }
+
+    /// Evaluate one kind of rule: the most specific root override that has an opinion
+    /// wins, then the global rules
+    fn matches(&self, dir_path: &Path, matcher: impl Fn(&RuleSet) -> &Gitignore) -> bool {
+        if self.is_never_skipped(dir_path) {
+            return false;
+        }
+
+        let root_rules = self.roots.iter()
+            .filter(|(root, _)| dir_path.starts_with(root) && dir_path != root.as_path())
+            .map(|(_, rule_set)| rule_set);
+
+        for rule_set in root_rules.chain(std::iter::once(&self.global)) {
+            match matcher(rule_set).matched(dir_path, true) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {},
+            }
+        }
+
+        false
+    }
+
+    fn is_never_skipped(&self, dir_path: &Path) -> bool {
+        let name_matches = dir_path.file_name()
+            .map(|name| name.to_string_lossy().to_lowercase())
+            .is_some_and(|name| self.never_skip_names.contains(&name));
+
+        name_matches || self.never_skip_paths.iter().any(|path| path == dir_path)
+    }
+}
+
+impl RuleSet {
+    fn compile(root: &Path, exclude: &[String], not_projects: &[String], include: &[String], on_error: &mut impl FnMut(String)) -> Self {
+        Self {
+            exclude: Self::matcher(root, exclude, include, on_error),
+            not_projects: Self::matcher(root, not_projects, include, on_error),
+        }
+    }
+
+    /// Gitignore matcher for `patterns`, with `include` added as negations so they win
+    fn matcher(root: &Path, patterns: &[String], include: &[String], on_error: &mut impl FnMut(String)) -> Gitignore {
+        let mut builder = GitignoreBuilder::new(root);
+        let _ = builder.case_insensitive(true);
+
+        let lines = patterns.iter().cloned().chain(include.iter().map(|pattern| format!("!{}", pattern)));
+        for line in lines {
+            if let Err(e) = builder.add_line(None, &line) {
+                on_error(format!("{} ({})", line, e));
+            }
+        }
+
+        builder.build().unwrap_or_else(|e| {
+            on_error(e.to_string());
+            Gitignore::empty()
+        })
+    }
+}
@@ -3,8 +3,12 @@ pub mod git_adapter;
 pub mod tokei_adapter;
 pub mod filesystem_adapter;
 pub mod ignore_adapter;
+pub mod watch_adapter;
+pub mod workspace_adapter;
 
 pub use git_adapter::*;
 pub use tokei_adapter::*;
 pub use filesystem_adapter::*;
-pub use ignore_adapter::*;
\ No newline at end of file
+pub use ignore_adapter::*;
+pub use watch_adapter::*;
+pub use workspace_adapter::*;
\ No newline at end of file
@@ -2,6 +2,7 @@
 use tokei::{Languages, Config, LanguageType};
 use std::path::Path;
 
+#[derive(Default)]
 pub struct TokeiAdapter;
 
 impl TokeiAdapter {
@@ -55,6 +56,12 @@ impl TokeiAdapter {
         }
         
         // For smaller directories, do a limited tokei scan
+        self.count_lines(dir_path)
+    }
+
+    /// Count lines with tokei, skipping dependency and build directories. Unlike
+    /// `analyze_languages` this always counts, whatever the directory's manifest or size.
+    pub fn count_lines(&self, dir_path: &Path) -> (Option<String>, usize, usize) {
         let mut languages = Languages::new();
         let config = Config::default();
         
@@ -0,0 +1,42 @@
+// Filesystem watching adapter - wraps notify with debouncing
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub type RepoDebouncer = Debouncer<RecommendedWatcher>;
+
+#[derive(Default)]
+pub struct WatchAdapter;
+
+impl WatchAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Create a debounced watcher that reports batches of changed paths
+    pub fn create_debounced_watcher<F>(&self, debounce: Duration, mut on_change: F) -> Result<RepoDebouncer, notify::Error>
+    where
+        F: FnMut(Vec<PathBuf>) + Send + 'static,
+    {
+        new_debouncer(debounce, move |result: DebounceEventResult| {
+            match result {
+                Ok(events) => {
+                    let paths = events.into_iter().map(|event| event.path).collect();
+                    on_change(paths);
+                },
+                Err(e) => println!("WATCH: Watcher error: {}", e),
+            }
+        })
+    }
+
+    /// Watch a single directory without descending into it
+    pub fn watch_directory(&self, debouncer: &mut RepoDebouncer, dir_path: &Path) -> Result<(), notify::Error> {
+        debouncer.watcher().watch(dir_path, RecursiveMode::NonRecursive)
+    }
+
+    /// Stop watching a directory
+    pub fn unwatch_directory(&self, debouncer: &mut RepoDebouncer, dir_path: &Path) -> Result<(), notify::Error> {
+        debouncer.watcher().unwatch(dir_path)
+    }
+}
@@ -0,0 +1,290 @@
+// Workspace manifest adapter - reads the members monorepo manifests declare
+use crate::models::WorkspaceKind;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::Path;
+
+static GO_MODULE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"(?m)^\s*module\s+["`]?([^\s"`]+)"#).unwrap());
+static XML_COMMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<!--.*?-->").unwrap());
+static MAVEN_MODULES: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<modules>(.*?)</modules>").unwrap());
+static MAVEN_MODULE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<module>\s*([^<]*?)\s*</module>").unwrap());
+static MAVEN_PARENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<parent>.*?</parent>").unwrap());
+static MAVEN_ARTIFACT_ID: Lazy<Regex> = Lazy::new(|| Regex::new(r"<artifactId>\s*([^<]*?)\s*</artifactId>").unwrap());
+
+/// Member patterns declared by one workspace manifest
+#[derive(Debug, Clone)]
+pub struct WorkspaceDeclaration {
+    pub kind: WorkspaceKind,
+    /// Member directories or globs, relative to the repository root
+    pub members: Vec<String>,
+    /// Directories or globs taken out of `members`
+    pub exclude: Vec<String>,
+}
+
+type ManifestParser = fn(&str) -> Option<(Vec<String>, Vec<String>)>;
+
+#[derive(Default)]
+pub struct WorkspaceAdapter;
+
+impl WorkspaceAdapter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Workspaces declared by the manifests at the root of `repo_path`. Manifests are read
+    /// leniently: one that doesn't parse declares no workspace.
+    pub fn find_workspaces(&self, repo_path: &Path) -> Vec<WorkspaceDeclaration> {
+        let parsers: [(WorkspaceKind, &str, ManifestParser); 5] = [
+            (WorkspaceKind::Cargo, "Cargo.toml", Self::parse_cargo),
+            (WorkspaceKind::Npm, "package.json", Self::parse_package_json),
+            (WorkspaceKind::Pnpm, "pnpm-workspace.yaml", Self::parse_pnpm),
+            (WorkspaceKind::Go, "go.work", Self::parse_go_work),
+            (WorkspaceKind::Maven, "pom.xml", Self::parse_maven),
+        ];
+
+        parsers.into_iter()
+            .filter_map(|(kind, manifest, parse)| {
+                let content = std::fs::read_to_string(repo_path.join(manifest)).ok()?;
+                let (members, exclude) = parse(&content)?;
+                Some(WorkspaceDeclaration { kind, members, exclude })
+            })
+            .filter(|workspace| !workspace.members.is_empty())
+            .collect()
+    }
+
+    /// Package, module or artifact name declared in a member's manifest
+    pub fn member_name(&self, kind: WorkspaceKind, member_path: &Path) -> Option<String> {
+        let content = std::fs::read_to_string(member_path.join(kind.member_manifest())).ok()?;
+
+        match kind {
+            WorkspaceKind::Cargo => Self::cargo_package_name(&content),
+            WorkspaceKind::Npm | WorkspaceKind::Pnpm => {
+                let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+                manifest.get("name")?.as_str().map(|name| name.to_string())
+            },
+            WorkspaceKind::Go => GO_MODULE.captures(&content).map(|captures| captures[1].to_string()),
+            WorkspaceKind::Maven => {
+                let content = XML_COMMENT.replace_all(&content, "");
+                let content = MAVEN_PARENT.replace_all(&content, "");
+                MAVEN_ARTIFACT_ID.captures(&content).map(|captures| captures[1].to_string())
+            },
+        }
+    }
+
+    /// `members` and `exclude` of the `[workspace]` table
+    fn parse_cargo(content: &str) -> Option<(Vec<String>, Vec<String>)> {
+        let manifest: toml::Table = toml::from_str(content).ok()?;
+        let workspace = manifest.get("workspace")?.as_table()?;
+        let members = Self::toml_strings(workspace.get("members")?);
+        let exclude = workspace.get("exclude").map(Self::toml_strings).unwrap_or_default();
+
+        Some((members, exclude))
+    }
+
+    /// `name` of the `[package]` table
+    fn cargo_package_name(content: &str) -> Option<String> {
+        let manifest: toml::Table = toml::from_str(content).ok()?;
+        manifest.get("package")?.get("name")?.as_str().map(|name| name.to_string())
+    }
+
+    /// `workspaces` as an array, or yarn's `{ "packages": [...] }` form
+    fn parse_package_json(content: &str) -> Option<(Vec<String>, Vec<String>)> {
+        let manifest: serde_json::Value = serde_json::from_str(content).ok()?;
+        let workspaces = manifest.get("workspaces")?;
+        let patterns = workspaces.get("packages").unwrap_or(workspaces).as_array()?;
+
+        Some(Self::split_negations(patterns.iter().filter_map(|pattern| pattern.as_str())))
+    }
+
+    /// The `packages` list of pnpm-workspace.yaml
+    fn parse_pnpm(content: &str) -> Option<(Vec<String>, Vec<String>)> {
+        let manifest: serde_yaml::Value = serde_yaml::from_str(content).ok()?;
+        let patterns = manifest.get("packages")?.as_sequence()?;
+
+        Some(Self::split_negations(patterns.iter().filter_map(|pattern| pattern.as_str())))
+    }
+
+    /// Directories named by `use` directives, single or in a `use ( ... )` block
+    fn parse_go_work(content: &str) -> Option<(Vec<String>, Vec<String>)> {
+        let mut members = Vec::new();
+        let mut in_block = false;
+
+        for line in content.lines() {
+            let line = line.split("//").next().unwrap_or("").trim();
+            if in_block {
+                match line {
+                    ")" => in_block = false,
+                    "" => {},
+                    dir => members.push(dir.trim_matches(['"', '`']).to_string()),
+                }
+            } else if let Some(rest) = line.strip_prefix("use").filter(|rest| rest.starts_with([' ', '\t', '('])) {
+                match rest.trim() {
+                    "(" => in_block = true,
+                    dir => members.push(dir.trim_matches(['"', '`']).to_string()),
+                }
+            }
+        }
+
+        Some((members, Vec::new()))
+    }
+
+    /// `<module>` entries of every `<modules>` block, including those in profiles
+    fn parse_maven(content: &str) -> Option<(Vec<String>, Vec<String>)> {
+        let content = XML_COMMENT.replace_all(content, "");
+        let members = MAVEN_MODULES.captures_iter(&content)
+            .flat_map(|modules| {
+                MAVEN_MODULE.captures_iter(modules.get(1).map_or("", |block| block.as_str()))
+                    .map(|module| module[1].to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        Some((members, Vec::new()))
+    }
+
+    /// Strings of a TOML array; anything else holds none
+    fn toml_strings(value: &toml::Value) -> Vec<String> {
+        value.as_array()
+            .map(|values| values.iter().filter_map(|value| value.as_str()).map(|value| value.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Separate `!pattern` exclusions from member patterns
+    fn split_negations<'a>(patterns: impl Iterator<Item = &'a str>) -> (Vec<String>, Vec<String>) {
+        let mut members = Vec::new();
+        let mut exclude = Vec::new();
+
+        for pattern in patterns.filter(|pattern| !pattern.is_empty()) {
+            match pattern.strip_prefix('!') {
+                Some(excluded) => exclude.push(excluded.to_string()),
+                None => members.push(pattern.to_string()),
+            }
+        }
+
+        (members, exclude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn cargo_workspace_table() {
+        let manifest = r#"
+[package]
+name = "root" # the root crate
+
+[workspace]  # members below
+members = [
+    "crates/*",
+    # "old-crate",
+    'tools/cli', # "commented-out"
+]
+exclude = ["crates/experimental"]
+resolver = "2"
+
+[workspace.dependencies]
+members = ["not-a-member"]
+"#;
+        let (members, exclude) = WorkspaceAdapter::parse_cargo(manifest).unwrap();
+        assert_eq!(members, strings(&["crates/*", "tools/cli"]));
+        assert_eq!(exclude, strings(&["crates/experimental"]));
+    }
+
+    #[test]
+    fn cargo_inline_workspace_table() {
+        let manifest = r#"
+workspace = { members = ["app", "lib#1"], exclude = ["scratch"] } # inline
+
+[package]
+name = "app"
+"#;
+        let (members, exclude) = WorkspaceAdapter::parse_cargo(manifest).unwrap();
+        assert_eq!(members, strings(&["app", "lib#1"]));
+        assert_eq!(exclude, strings(&["scratch"]));
+    }
+
+    #[test]
+    fn cargo_without_workspace() {
+        assert!(WorkspaceAdapter::parse_cargo("[package]\nname = \"solo\"\n# [workspace]\n").is_none());
+        assert!(WorkspaceAdapter::parse_cargo("[workspace.dependencies]\nserde = \"1\"\n").is_none());
+    }
+
+    #[test]
+    fn package_json_workspaces() {
+        let (members, exclude) = WorkspaceAdapter::parse_package_json(r#"{ "workspaces": ["packages/*", "!packages/private", ""] }"#).unwrap();
+        assert_eq!(members, strings(&["packages/*"]));
+        assert_eq!(exclude, strings(&["packages/private"]));
+
+        let (members, _) = WorkspaceAdapter::parse_package_json(r#"{ "workspaces": { "packages": ["apps/*"], "nohoist": ["**/x"] } }"#).unwrap();
+        assert_eq!(members, strings(&["apps/*"]));
+
+        assert!(WorkspaceAdapter::parse_package_json(r#"{ "name": "solo" }"#).is_none());
+        assert!(WorkspaceAdapter::parse_package_json("not json").is_none());
+    }
+
+    #[test]
+    fn pnpm_packages() {
+        let manifest = r#"
+# workspace layout
+packages:
+  # every app
+  - 'apps/*'
+  - "packages/**" # nested too
+  - '!**/test/**'
+
+catalog:
+  - react
+"#;
+        let (members, exclude) = WorkspaceAdapter::parse_pnpm(manifest).unwrap();
+        assert_eq!(members, strings(&["apps/*", "packages/**"]));
+        assert_eq!(exclude, strings(&["**/test/**"]));
+
+        let (members, exclude) = WorkspaceAdapter::parse_pnpm("packages: ['libs/*', \"!libs/old\"] # flow style\n").unwrap();
+        assert_eq!(members, strings(&["libs/*"]));
+        assert_eq!(exclude, strings(&["libs/old"]));
+
+        assert!(WorkspaceAdapter::parse_pnpm("# packages:\ncatalog:\n  - react\n").is_none());
+    }
+
+    #[test]
+    fn go_work_use_directives() {
+        let manifest = "go 1.22\n\nuse ./tools // single\n\nuse (\n\t./api\n\t// ./old\n\t\"./web\"\n)\n";
+        let (members, exclude) = WorkspaceAdapter::parse_go_work(manifest).unwrap();
+        assert_eq!(members, strings(&["./tools", "./api", "./web"]));
+        assert!(exclude.is_empty());
+    }
+
+    #[test]
+    fn maven_modules() {
+        let manifest = r#"
+<project>
+  <modules>
+    <module>core</module>
+    <!-- <module>legacy</module> -->
+    <module> web </module>
+  </modules>
+  <profiles>
+    <profile>
+      <modules><module>extras</module></modules>
+    </profile>
+  </profiles>
+</project>
+"#;
+        let (members, exclude) = WorkspaceAdapter::parse_maven(manifest).unwrap();
+        assert_eq!(members, strings(&["core", "web", "extras"]));
+        assert!(exclude.is_empty());
+    }
+
+    #[test]
+    fn cargo_package_name() {
+        let manifest = "[package]\n# name = \"old\"\nname = \"new\" # renamed\n\n[dependencies]\nname = \"dep\"\n";
+        assert_eq!(WorkspaceAdapter::cargo_package_name(manifest), Some("new".to_string()));
+        assert_eq!(WorkspaceAdapter::cargo_package_name("[package]\nname.workspace = true\n"), None);
+    }
+}
@@ -1,15 +1,22 @@
 // Cache service implementation - handles save/load operations and directory management
 use super::models::*;
-use crate::models::Repository;
+use crate::models::{DeletedBranchRecord, DiscardBackup, Repository, ScanRules};
 use chrono::Utc;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
 
+/// Deleted branch tips kept for restoring; older records are dropped first
+const MAX_DELETED_BRANCH_RECORDS: usize = 1000;
+
+/// Held for every read-modify-write of the cache directory's files, since scans, the
+/// watcher and the activity caches all update them concurrently
+static CACHE_WRITE_LOCK: once_cell::sync::Lazy<tokio::sync::Mutex<()>> =
+    once_cell::sync::Lazy::new(|| tokio::sync::Mutex::new(()));
+
 /// Cache service for managing repository data persistence
 pub struct CacheService {
-    app_handle: AppHandle,
     cache_dir: PathBuf,
 }
 
@@ -32,7 +39,6 @@ impl CacheService {
         }
         
         Ok(Self {
-            app_handle,
             cache_dir,
         })
     }
@@ -53,7 +59,7 @@ impl CacheService {
         self.cache_dir.join("repositories.json")
     }
     
-    /// Get the preferences file path (handled by tauri-plugin-store)
+    /// Get the preferences file path
     fn get_preferences_file_path(&self) -> PathBuf {
         self.cache_dir.join("preferences.json")
     }
@@ -83,8 +89,154 @@ impl CacheService {
         Ok(Some(cache_data))
     }
     
+    /// Get the deleted branch record file path
+    fn get_deleted_branches_file_path(&self) -> PathBuf {
+        self.cache_dir.join("deleted_branches.json")
+    }
+    
+    /// Directory that discarded working-tree changes are copied into before being discarded
+    pub fn get_discard_backup_dir(&self) -> PathBuf {
+        self.cache_dir.join("discarded")
+    }
+    
+    fn get_discard_backups_file_path(&self) -> PathBuf {
+        self.cache_dir.join("discard_backups.json")
+    }
+    
+    /// Load the record of discard backups, newest first
+    pub async fn load_discard_backups(&self) -> Result<Vec<DiscardBackup>, Box<dyn std::error::Error + Send + Sync>> {
+        let records_file = self.get_discard_backups_file_path();
+        
+        if !records_file.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let content = fs::read_to_string(&records_file)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+    
+    /// Add a discard backup to the record. Backups are never pruned automatically since
+    /// they are the only copy of the discarded changes.
+    pub async fn record_discard_backup(&self, backup: DiscardBackup) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _lock = CACHE_WRITE_LOCK.lock().await;
+        let mut records = vec![backup];
+        records.extend(self.load_discard_backups().await?);
+        
+        let content = serde_json::to_string_pretty(&records)?;
+        Self::write_atomic(&self.get_discard_backups_file_path(), &content)?;
+        
+        Ok(())
+    }
+    
+    /// Load the record of deleted branch tips, newest first
+    pub async fn load_deleted_branches(&self) -> Result<Vec<DeletedBranchRecord>, Box<dyn std::error::Error + Send + Sync>> {
+        let records_file = self.get_deleted_branches_file_path();
+        
+        if !records_file.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let content = fs::read_to_string(&records_file)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+    
+    /// Add deleted branch tips to the record, keeping at most `MAX_DELETED_BRANCH_RECORDS`
+    pub async fn record_deleted_branches(&self, deleted: Vec<DeletedBranchRecord>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _lock = CACHE_WRITE_LOCK.lock().await;
+        let mut records = deleted;
+        records.extend(self.load_deleted_branches().await?);
+        records.truncate(MAX_DELETED_BRANCH_RECORDS);
+        
+        let content = serde_json::to_string_pretty(&records)?;
+        Self::write_atomic(&self.get_deleted_branches_file_path(), &content)?;
+        
+        Ok(())
+    }
+    
+    /// Remove the deleted branch records `forget` selects, e.g. branches whose deletion
+    /// failed after they were recorded, or that were restored
+    pub async fn forget_deleted_branches<F>(&self, forget: F) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: Fn(&DeletedBranchRecord) -> bool,
+    {
+        let _lock = CACHE_WRITE_LOCK.lock().await;
+        let mut remaining = self.load_deleted_branches().await?;
+        remaining.retain(|record| !forget(record));
+        
+        let content = serde_json::to_string_pretty(&remaining)?;
+        Self::write_atomic(&self.get_deleted_branches_file_path(), &content)?;
+        
+        Ok(())
+    }
+    
+    /// Load the scan rules, falling back to the defaults if preferences can't be read
+    pub async fn load_scan_rules(&self) -> ScanRules {
+        match self.load_preferences().await {
+            Ok(preferences) => preferences.scan_rules,
+            Err(e) => {
+                println!("⚠️  Failed to load scan rules, using defaults: {}", e);
+                ScanRules::default()
+            }
+        }
+    }
+    
+    /// Load user preferences, falling back to defaults when none have been saved
+    pub async fn load_preferences(&self) -> Result<UserPreferences, Box<dyn std::error::Error + Send + Sync>> {
+        let preferences_file = self.get_preferences_file_path();
+        
+        if !preferences_file.exists() {
+            return Ok(UserPreferences::default());
+        }
+        
+        let content = fs::read_to_string(&preferences_file)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+    
+    /// Save user preferences
+    pub async fn save_preferences(&self, preferences: &UserPreferences) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _lock = CACHE_WRITE_LOCK.lock().await;
+        self.write_preferences(preferences)
+    }
+    
+    /// Load, change and save the user preferences without racing other writers
+    pub async fn update_preferences(&self, update: impl FnOnce(&mut UserPreferences)) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _lock = CACHE_WRITE_LOCK.lock().await;
+        let mut preferences = self.load_preferences().await?;
+        update(&mut preferences);
+        self.write_preferences(&preferences)
+    }
+    
+    fn write_preferences(&self, preferences: &UserPreferences) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let content = serde_json::to_string_pretty(preferences)?;
+        Self::write_atomic(&self.get_preferences_file_path(), &content)?;
+        
+        println!("Saved preferences to: {}", self.get_preferences_file_path().display());
+        Ok(())
+    }
+    
     /// Save repository data to cache
     pub async fn save_cache(&self, cache_data: &CacheData) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _lock = CACHE_WRITE_LOCK.lock().await;
+        self.write_cache(cache_data).await
+    }
+    
+    /// Replace the cache with what `update` builds from the current one (None if there is
+    /// none or it can't be read). Nothing is saved if `update` returns None.
+    pub async fn update_cache(&self, update: impl FnOnce(Option<CacheData>) -> Option<CacheData>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _lock = CACHE_WRITE_LOCK.lock().await;
+        let current = self.load_cache().await.unwrap_or_else(|e| {
+            println!("⚠️  Failed to load cache, replacing it: {}", e);
+            None
+        });
+        
+        match update(current) {
+            Some(cache_data) => self.write_cache(&cache_data).await,
+            None => Ok(()),
+        }
+    }
+    
+    /// Write the cache with a historical backup; callers hold `CACHE_WRITE_LOCK`
+    async fn write_cache(&self, cache_data: &CacheData) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let cache_file = self.get_cache_file_path();
         
         println!("Saving cache to: {}", cache_file.display());
@@ -97,7 +249,7 @@ impl CacheService {
         
         // Write new cache data
         let content = serde_json::to_string_pretty(cache_data)?;
-        fs::write(&cache_file, content)?;
+        Self::write_atomic(&cache_file, &content)?;
         
         println!("Saved cache with {} repositories", cache_data.repositories.len());
         
@@ -107,33 +259,91 @@ impl CacheService {
         Ok(())
     }
     
+    /// Apply incremental updates (e.g. from the filesystem watcher) to the saved cache.
+    /// Unlike `save_cache` this doesn't create a historical backup for every change.
+    pub async fn update_entries(
+        &self,
+        updated: Vec<CachedRepository>,
+        removed: &[String],
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _lock = CACHE_WRITE_LOCK.lock().await;
+        let mut cache_data = match self.load_cache().await? {
+            Some(cache_data) => cache_data,
+            None => return Ok(()),
+        };
+        
+        for path in removed {
+            cache_data.repositories.remove(path);
+            cache_data.checksums.remove(path);
+        }
+        
+        for mut cached_repo in updated {
+            let path = cached_repo.repository.path.clone();
+            if let Some(previous) = cache_data.repositories.get(&path) {
+                cached_repo.carry_over_from(previous);
+            }
+            match &cached_repo.git_head_sha {
+                Some(sha) => cache_data.checksums.insert(path.clone(), sha.clone()),
+                None => cache_data.checksums.remove(&path),
+            };
+            cache_data.repositories.insert(path, cached_repo);
+        }
+        
+        cache_data.recalculate_totals();
+        
+        let content = serde_json::to_string_pretty(&cache_data)?;
+        Self::write_atomic(&self.get_cache_file_path(), &content)?;
+        
+        Ok(())
+    }
+    
+    /// Write `content` to a temporary file next to `path` and rename it over `path`, so
+    /// a crash mid-write never leaves a truncated file behind
+    fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+        let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        let temp_path = path.with_file_name(format!("{}.tmp", file_name));
+        
+        fs::write(&temp_path, content)?;
+        fs::rename(&temp_path, path)
+    }
+    
     /// Create a new cache data structure from repositories
     pub fn create_cache_data(
         &self,
         repositories: Vec<Repository>,
         scanned_paths: Vec<PathBuf>,
-        checksums: HashMap<String, String>,
+        fingerprints: HashMap<String, RepoFingerprint>,
     ) -> CacheData {
         let total_repos = repositories.len();
         let total_git_repos = repositories.iter().filter(|r| r.is_git_repo).count();
         let total_size_mb = repositories.iter().map(|r| r.size_mb).sum();
         
-        let cached_repos = repositories
+        let cached_repos: HashMap<String, CachedRepository> = repositories
             .into_iter()
             .map(|repo| {
                 let path = repo.path.clone();
-                let git_head_sha = checksums.get(&path).cloned();
-                let cached_repo = CachedRepository::new(repo, git_head_sha);
+                let fingerprint = fingerprints.get(&path).cloned().unwrap_or_default();
+                let mut cached_repo = CachedRepository::new(repo, fingerprint.git_head_sha);
+                cached_repo.last_modified = fingerprint.last_modified;
+                cached_repo.worktree_signature = fingerprint.worktree_signature;
                 (path, cached_repo)
             })
             .collect();
         
+        let checksums = cached_repos
+            .iter()
+            .filter_map(|(path, cached_repo)| {
+                cached_repo.git_head_sha.clone().map(|sha| (path.clone(), sha))
+            })
+            .collect();
+        
         CacheData {
             version: CACHE_VERSION.to_string(),
             last_scan: Utc::now(),
             scanned_paths,
             repositories: cached_repos,
             checksums,
+            incomplete_paths: Vec::new(),
             total_repos,
             total_git_repos,
             total_size_mb,
@@ -149,33 +359,28 @@ impl CacheService {
             .collect()
     }
     
-    /// Check which repositories need updating based on checksums
+    /// Check which repositories need updating based on their fingerprints
     pub fn find_stale_repositories(
         &self,
         cache_data: &CacheData,
-        current_checksums: &HashMap<String, String>,
+        current_fingerprints: &HashMap<String, RepoFingerprint>,
     ) -> Vec<String> {
         let mut stale_paths = Vec::new();
         
         // Check for changed repositories
         for (path, cached_repo) in &cache_data.repositories {
-            if let Some(current_checksum) = current_checksums.get(path) {
-                if let Some(cached_checksum) = &cached_repo.git_head_sha {
-                    if cached_checksum != current_checksum {
-                        stale_paths.push(path.clone());
-                    }
-                } else {
-                    // No cached checksum, consider stale
+            if let Some(current_fingerprint) = current_fingerprints.get(path) {
+                if !cached_repo.matches_fingerprint(current_fingerprint) {
                     stale_paths.push(path.clone());
                 }
             } else {
-                // Repository no longer exists
+                // Repository no longer exists (or wasn't part of this scan)
                 stale_paths.push(path.clone());
             }
         }
         
         // Check for new repositories
-        for path in current_checksums.keys() {
+        for path in current_fingerprints.keys() {
             if !cache_data.repositories.contains_key(path) {
                 stale_paths.push(path.clone());
             }
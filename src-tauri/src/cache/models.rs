@@ -1,8 +1,8 @@
 // Cache data models and serialization structures
-use crate::models::Repository;
-use chrono::{DateTime, Utc};
+use crate::models::{CommitHeatmap, GitCommandPolicy, RepoActivity, Repository, ScanRules};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 /// Version of the cache format for backwards compatibility
@@ -26,6 +26,10 @@ pub struct CacheData {
     /// Git HEAD checksums for change detection (path -> git_head_sha)
     pub checksums: HashMap<String, String>,
     
+    /// Scanned paths whose last scan was cancelled, so their results are partial
+    #[serde(default)]
+    pub incomplete_paths: Vec<PathBuf>,
+    
     /// Total statistics
     pub total_repos: usize,
     pub total_git_repos: usize,
@@ -47,12 +51,61 @@ pub struct CachedRepository {
     /// Directory last modified time for non-Git repos
     pub last_modified: Option<DateTime<Utc>>,
     
+    /// Digest of uncommitted working tree changes at time of caching (if Git repo)
+    #[serde(default)]
+    pub worktree_signature: Option<String>,
+    
     /// Whether this cache entry is considered stale
     pub is_stale: bool,
+    
+    /// Last computed commit activity, valid while HEAD stays at the recorded SHA
+    #[serde(default)]
+    pub activity: Option<CachedActivity>,
+    
+    /// Daily commit counts for the heatmap, valid while HEAD stays at the recorded SHA
+    #[serde(default)]
+    pub heatmap: Option<CachedHeatmap>,
 }
 
-/// User preferences and settings
+/// Commit activity computed for a specific HEAD and window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedActivity {
+    pub head_sha: String,
+    pub days: u32,
+    pub computed_at: DateTime<Utc>,
+    pub activity: RepoActivity,
+}
+
+/// Daily commit counts since `since`, computed for a specific HEAD
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedHeatmap {
+    pub head_sha: String,
+    pub since: NaiveDate,
+    pub computed_at: DateTime<Utc>,
+    /// Only days with commits are stored
+    pub daily_commits: BTreeMap<NaiveDate, u32>,
+    /// The window held more commits than were walked, so the counts are partial
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Change-detection markers captured for a directory during a scan
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepoFingerprint {
+    /// Git HEAD SHA (Git repos only)
+    pub git_head_sha: Option<String>,
+    
+    /// `.git/index` modified time for Git repos, directory modified time otherwise
+    pub last_modified: Option<DateTime<Utc>>,
+    
+    /// Digest of uncommitted working tree changes (Git repos with a dirty tree only), so
+    /// edits that don't touch the index still invalidate the entry
+    #[serde(default)]
+    pub worktree_signature: Option<String>,
+}
+
+/// User preferences and settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UserPreferences {
     /// Recently scanned directory paths
     pub recent_paths: Vec<PathBuf>,
@@ -65,6 +118,14 @@ pub struct UserPreferences {
     
     /// Cache settings
     pub cache_settings: CacheSettings,
+    
+    /// Which commands `execute_git_command` and Custom batch operations may run
+    #[serde(default)]
+    pub git_command_policy: GitCommandPolicy,
+    
+    /// Which directories scans and the watcher look at
+    #[serde(default)]
+    pub scan_rules: ScanRules,
 }
 
 /// UI-related user preferences
@@ -115,17 +176,6 @@ pub struct CacheSettings {
     pub max_cache_size_mb: u32,
 }
 
-impl Default for UserPreferences {
-    fn default() -> Self {
-        Self {
-            recent_paths: Vec::new(),
-            last_scan_time: None,
-            ui_preferences: UIPreferences::default(),
-            cache_settings: CacheSettings::default(),
-        }
-    }
-}
-
 impl Default for UIPreferences {
     fn default() -> Self {
         Self {
@@ -167,6 +217,7 @@ impl Default for CacheData {
             scanned_paths: Vec::new(),
             repositories: HashMap::new(),
             checksums: HashMap::new(),
+            incomplete_paths: Vec::new(),
             total_repos: 0,
             total_git_repos: 0,
             total_size_mb: 0.0,
@@ -174,6 +225,65 @@ impl Default for CacheData {
     }
 }
 
+impl CacheData {
+    /// Recompute the summary totals after repositories were added or removed
+    pub fn recalculate_totals(&mut self) {
+        self.total_repos = self.repositories.len();
+        self.total_git_repos = self.repositories.values()
+            .filter(|cached_repo| cached_repo.repository.is_git_repo)
+            .count();
+        self.total_size_mb = self.repositories.values()
+            .map(|cached_repo| cached_repo.repository.size_mb)
+            .sum();
+    }
+    
+    /// Carry history-derived data over from the previous cache for unchanged repositories
+    pub fn carry_over_from(&mut self, previous: &CacheData) {
+        for (path, cached_repo) in self.repositories.iter_mut() {
+            if let Some(previous_repo) = previous.repositories.get(path) {
+                cached_repo.carry_over_from(previous_repo);
+            }
+        }
+    }
+}
+
+impl CachedActivity {
+    pub fn new(head_sha: String, days: u32, activity: RepoActivity) -> Self {
+        Self {
+            head_sha,
+            days,
+            computed_at: Utc::now(),
+            activity,
+        }
+    }
+    
+    /// Whether this result still answers a request for `days` at `head_sha`.
+    /// Windows are relative to today, so results from an earlier day are recomputed.
+    pub fn is_current(&self, head_sha: &str, days: u32) -> bool {
+        self.head_sha == head_sha
+            && self.days == days
+            && self.computed_at.date_naive() == Utc::now().date_naive()
+    }
+}
+
+impl CachedHeatmap {
+    pub fn new(head_sha: String, since: NaiveDate, daily_commits: BTreeMap<NaiveDate, u32>, truncated: bool) -> Self {
+        Self {
+            head_sha,
+            since,
+            computed_at: Utc::now(),
+            daily_commits,
+            truncated,
+        }
+    }
+    
+    /// Whether the counts still cover today's grid at `head_sha`. The grid only moves
+    /// forward in time, so counts stay usable until HEAD changes.
+    pub fn is_current(&self, head_sha: &str) -> bool {
+        self.head_sha == head_sha && self.since <= CommitHeatmap::grid_start(Utc::now().date_naive())
+    }
+}
+
 impl CachedRepository {
     /// Create a new cached repository entry
     pub fn new(repository: Repository, git_head_sha: Option<String>) -> Self {
@@ -182,8 +292,46 @@ impl CachedRepository {
             cached_at: Utc::now(),
             git_head_sha,
             last_modified: None,
+            worktree_signature: None,
             is_stale: false,
+            activity: None,
+            heatmap: None,
+        }
+    }
+    
+    /// Keep history-derived data from a previous entry for the same HEAD, so
+    /// rescans don't throw away work that only depends on the commit graph
+    pub fn carry_over_from(&mut self, previous: &CachedRepository) {
+        if self.git_head_sha.is_none() || self.git_head_sha != previous.git_head_sha {
+            return;
+        }
+        
+        if self.activity.is_none() {
+            self.activity = previous.activity.clone();
+        }
+        if self.heatmap.is_none() {
+            self.heatmap = previous.heatmap.clone();
+        }
+    }
+    
+    /// Change-detection markers recorded for this entry
+    pub fn fingerprint(&self) -> RepoFingerprint {
+        RepoFingerprint {
+            git_head_sha: self.git_head_sha.clone(),
+            last_modified: self.last_modified,
+            worktree_signature: self.worktree_signature.clone(),
+        }
+    }
+    
+    /// Check whether the recorded markers still match the directory on disk
+    pub fn matches_fingerprint(&self, fingerprint: &RepoFingerprint) -> bool {
+        // Without any recorded markers we can't prove the entry is current
+        if self.git_head_sha.is_none() && self.last_modified.is_none() {
+            return false;
         }
+        
+        self.git_head_sha == fingerprint.git_head_sha && self.last_modified == fingerprint.last_modified
+            && self.worktree_signature == fingerprint.worktree_signature
     }
     
     /// Check if this cache entry should be considered stale
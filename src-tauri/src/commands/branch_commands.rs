@@ -0,0 +1,43 @@
+// Tauri command handlers for stale branch reports and cleanup
+use crate::models::*;
+use crate::cache::CacheService;
+use crate::services::{BranchService, DEFAULT_INACTIVE_BRANCH_DAYS};
+use tauri::AppHandle;
+
+static BRANCH_SERVICE: once_cell::sync::Lazy<BranchService> =
+    once_cell::sync::Lazy::new(BranchService::new);
+
+#[tauri::command]
+pub async fn get_stale_branches(app: AppHandle, inactive_days: Option<u32>) -> Result<StaleBranchReport, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+    let inactive_days = inactive_days.unwrap_or(DEFAULT_INACTIVE_BRANCH_DAYS);
+
+    BRANCH_SERVICE.stale_branch_report(&cache_service, inactive_days).await
+}
+
+/// Delete selected branches. Defaults to a dry run unless `dry_run` is explicitly false.
+#[tauri::command]
+pub async fn delete_branches(app: AppHandle, branches: Vec<BranchDeletionRequest>, dry_run: Option<bool>) -> Result<BranchCleanupResult, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+
+    BRANCH_SERVICE.delete_branches(&cache_service, branches, dry_run.unwrap_or(true)).await
+}
+
+#[tauri::command]
+pub async fn get_deleted_branches(app: AppHandle) -> Result<Vec<DeletedBranchRecord>, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+
+    cache_service.load_deleted_branches().await
+        .map_err(|e| format!("Failed to load deleted branches: {}", e))
+}
+
+#[tauri::command]
+pub async fn restore_branch(app: AppHandle, repo_path: String, branch: String, tip_sha: String) -> Result<(), String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+
+    BRANCH_SERVICE.restore_branch(&cache_service, repo_path, branch, tip_sha).await
+}
@@ -0,0 +1,183 @@
+// Tauri command handlers for Git operations on individual and multiple repositories
+use crate::models::*;
+use crate::adapters::GitAdapter;
+use crate::cache::CacheService;
+use crate::services::{ActivityService, BatchService, DEFAULT_ACTIVITY_DAYS, DEFAULT_BATCH_PARALLELISM};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+static BATCH_SERVICE: once_cell::sync::Lazy<Arc<BatchService>> =
+    once_cell::sync::Lazy::new(|| Arc::new(BatchService::new()));
+
+static ACTIVITY_SERVICE: once_cell::sync::Lazy<ActivityService> =
+    once_cell::sync::Lazy::new(ActivityService::new);
+
+static NEXT_BATCH_ID: AtomicU64 = AtomicU64::new(1);
+
+#[tauri::command]
+pub async fn get_repo_status(repo_path: String) -> Result<GitStatus, String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.get_status_detailed(&path).await {
+        Ok(status) => Ok(status),
+        Err(e) => Err(format!("Failed to get repository status: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn get_repo_remotes(repo_path: String) -> Result<Vec<RemoteInfo>, String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.get_remotes(&path).await {
+        Ok(remotes) => Ok(remotes),
+        Err(e) => Err(format!("Failed to get repository remotes: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn get_repo_branches(repo_path: String) -> Result<Vec<BranchInfo>, String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.get_branches(&path).await {
+        Ok(branches) => Ok(branches),
+        Err(e) => Err(format!("Failed to get repository branches: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn get_repo_submodules(repo_path: String) -> Result<Vec<SubmoduleInfo>, String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.list_submodules(&path) {
+        Ok(submodules) => Ok(submodules),
+        Err(e) => Err(format!("Failed to get repository submodules: {}", e))
+    }
+}
+
+/// Structured diff of one file; `staged` selects index vs HEAD instead of working tree vs index
+#[tauri::command]
+pub async fn get_diff(repo_path: String, path: String, staged: Option<bool>) -> Result<FileDiff, String> {
+    let git_adapter = GitAdapter::new();
+    let repo_path = PathBuf::from(repo_path);
+
+    match git_adapter.get_diff(&repo_path, &path, staged.unwrap_or(false)) {
+        Ok(diff) => Ok(diff),
+        Err(e) => Err(format!("Failed to get diff: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn get_repo_activity(app: AppHandle, repo_path: String, days: Option<u32>) -> Result<RepoActivity, String> {
+    let cache_service = CacheService::new(app).ok();
+    let days = days.unwrap_or(DEFAULT_ACTIVITY_DAYS);
+
+    match ACTIVITY_SERVICE.get_activity(cache_service.as_ref(), &repo_path, days).await {
+        Ok(activity) => Ok(activity),
+        Err(e) => Err(format!("Failed to get repository activity: {}", e))
+    }
+}
+
+/// Per-author commits, line changes and active days across all cached Git repositories
+#[tauri::command]
+pub async fn get_contribution_report(app: AppHandle, days: Option<u32>) -> Result<ContributionReport, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+    let days = days.unwrap_or(DEFAULT_ACTIVITY_DAYS);
+
+    ACTIVITY_SERVICE.contribution_report(&cache_service, days).await
+}
+
+/// Daily commit counts for the heatmap: one repository, or the whole workspace when `repo_path` is omitted
+#[tauri::command]
+pub async fn get_commit_heatmap(app: AppHandle, repo_path: Option<String>) -> Result<CommitHeatmap, String> {
+    let cache_service = CacheService::new(app).ok();
+
+    match ACTIVITY_SERVICE.commit_heatmap(cache_service.as_ref(), repo_path.as_deref()).await {
+        Ok(heatmap) => Ok(heatmap),
+        Err(e) => Err(format!("Failed to get commit heatmap: {}", e))
+    }
+}
+
+/// Run a user-supplied git command after checking it against the saved command policy.
+/// Destructive commands (e.g. `push --force`) are refused unless `confirmed` is set.
+#[tauri::command]
+pub async fn execute_git_command(app: AppHandle, repo_path: String, command: Vec<String>, confirmed: Option<bool>) -> Result<GitCommandResult, String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+    let policy = load_command_policy(app).await?;
+
+    // Accept "git status" as well as "status"
+    let command: Vec<String> = match command.first() {
+        Some(first) if first == "git" => command.into_iter().skip(1).collect(),
+        _ => command,
+    };
+    policy.check(&command, confirmed.unwrap_or(false))?;
+
+    match git_adapter.execute_command(&path, &command, Duration::from_secs(policy.timeout_secs)).await {
+        Ok(result) => Ok(result),
+        Err(e) => Err(format!("Failed to execute git command: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn get_git_command_policy(app: AppHandle) -> Result<GitCommandPolicy, String> {
+    load_command_policy(app).await
+}
+
+#[tauri::command]
+pub async fn set_git_command_policy(app: AppHandle, policy: GitCommandPolicy) -> Result<(), String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+    cache_service.update_preferences(|preferences| preferences.git_command_policy = policy).await
+        .map_err(|e| format!("Failed to save preferences: {}", e))
+}
+
+async fn load_command_policy(app: AppHandle) -> Result<GitCommandPolicy, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+    match cache_service.load_preferences().await {
+        Ok(preferences) => Ok(preferences.git_command_policy),
+        Err(e) => Err(format!("Failed to load preferences: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn batch_git_operation(app: AppHandle, repos: Vec<String>, operation: BatchOperation, parallelism: Option<usize>) -> Result<BatchResult, String> {
+    let batch_id = format!("batch-{}", NEXT_BATCH_ID.fetch_add(1, Ordering::Relaxed));
+    let repo_paths: Vec<PathBuf> = repos.into_iter().map(PathBuf::from).collect();
+    let parallelism = parallelism.unwrap_or(DEFAULT_BATCH_PARALLELISM);
+    let started = Instant::now();
+    let policy = load_command_policy(app.clone()).await?;
+
+    // Refuse a blocked or unconfirmed Custom command once, rather than once per repository
+    if operation.operation_type == BatchOperationType::Custom {
+        if let Ok(args) = BatchService::command_parameter(&operation) {
+            policy.check(&args, BatchService::confirmed_parameter(&operation))?;
+        }
+    }
+
+    // Stream per-repository progress, mirroring scan-progress
+    let app_handle_progress = app.clone();
+    let result = BATCH_SERVICE.run(&batch_id, repo_paths, operation, policy, parallelism, |progress| {
+        println!("BATCH: {} {:?} ({}/{})", progress.repo_path, progress.state, progress.completed_count, progress.total_count);
+        let _ = app_handle_progress.emit("batch-progress", progress);
+    }).await;
+    println!("BATCH: {} succeeded, {} failed", result.successful, result.failed);
+
+    let _ = app.emit("batch-complete", serde_json::json!({
+        "batch_id": batch_id,
+        "total_repos": result.total_repos,
+        "successful": result.successful,
+        "failed": result.failed,
+        "elapsed_ms": started.elapsed().as_millis() as u64
+    }));
+
+    Ok(result)
+}
@@ -1,4 +1,14 @@
 // Commands - Tauri command handlers
 pub mod repository_commands;
+pub mod watcher_commands;
+pub mod git_commands;
+pub mod branch_commands;
+pub mod stash_commands;
+pub mod working_tree_commands;
 
-pub use repository_commands::*;
\ No newline at end of file
+pub use repository_commands::*;
+pub use watcher_commands::*;
+pub use git_commands::*;
+pub use branch_commands::*;
+pub use stash_commands::*;
+pub use working_tree_commands::*;
\ No newline at end of file
@@ -1,111 +1,277 @@
 // Tauri command handlers for repository operations
 use crate::models::*;
-use crate::services::RepositoryService;
+use crate::adapters::IgnoreAdapter;
+use crate::services::{RepositoryService, ScanHandle};
 use crate::cache::CacheService;
+use super::watcher_commands::restart_watcher;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
 use tauri::{AppHandle, Emitter};
 
-// Static service instance for Tauri commands
-static REPO_SERVICE: once_cell::sync::Lazy<Arc<Mutex<RepositoryService>>> = 
-    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(RepositoryService::new())));
+// Static service instance for Tauri commands. The service is stateless, so commands
+// share it without locking and stats/status queries can run while a scan is in progress.
+static REPO_SERVICE: once_cell::sync::Lazy<Arc<RepositoryService>> = 
+    once_cell::sync::Lazy::new(|| Arc::new(RepositoryService::new()));
+
+// Running scans by ID, so cancel_scan can reach them
+static ACTIVE_SCANS: once_cell::sync::Lazy<Mutex<HashMap<String, ScanHandle>>> = 
+    once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_SCAN_ID: AtomicU64 = AtomicU64::new(1);
 
 #[tauri::command]
-pub async fn scan_repositories(app: AppHandle, directory_path: String, add_mode: Option<bool>) -> Result<Vec<Repository>, String> {
-    println!("Starting scan of: {}", directory_path);
+pub async fn scan_repositories(app: AppHandle, directory_path: String, add_mode: Option<bool>, save_partial: Option<bool>) -> Result<Vec<Repository>, String> {
+    let scan_id = format!("scan-{}", NEXT_SCAN_ID.fetch_add(1, Ordering::Relaxed));
+    println!("Starting scan {} of: {}", scan_id, directory_path);
     let path = Path::new(&directory_path);
-    let service = REPO_SERVICE.lock().await;
+    let service = Arc::clone(&REPO_SERVICE);
+    
+    let scan = ScanHandle::new();
+    if let Ok(mut active_scans) = ACTIVE_SCANS.lock() {
+        active_scans.insert(scan_id.clone(), scan.clone());
+    }
+    
+    // Announce the scan ID before the (potentially long) directory walk
+    let _ = app.emit("scan-progress", serde_json::json!({
+        "scan_id": scan_id,
+        "current_directory": directory_path,
+        "scanned_count": 0,
+        "total_count": 0
+    }));
+    
+    // Load the existing cache so unchanged directories can skip re-analysis
+    let cache_service = match CacheService::new(app.clone()) {
+        Ok(cache_service) => Some(cache_service),
+        Err(e) => {
+            println!("⚠️  Failed to create cache service: {}", e);
+            None
+        }
+    };
+    let existing_cache = match &cache_service {
+        Some(cache_service) => cache_service.load_cache().await.unwrap_or_else(|e| {
+            println!("⚠️  Failed to load cache, doing a full scan: {}", e);
+            None
+        }),
+        None => None,
+    };
     
-    println!("Got service lock, starting scan...");
+    let rules = match &cache_service {
+        Some(cache_service) => cache_service.load_scan_rules().await,
+        None => ScanRules::default(),
+    };
+    
+    // Walking the tree and stat-ing every directory blocks, so keep it off the async workers
+    let discovery = {
+        let service = Arc::clone(&service);
+        let base_path = path.to_path_buf();
+        let rules = rules.clone();
+        let scan = scan.clone();
+        tokio::task::spawn_blocking(move || {
+            let dirs_to_scan = service.discover_directories(&base_path, &rules, &scan);
+            let fingerprints = service.collect_fingerprints(&dirs_to_scan, &scan);
+            (dirs_to_scan, fingerprints)
+        }).await
+    };
+    let (dirs_to_scan, fingerprints) = match discovery {
+        Ok(discovery) => discovery,
+        Err(e) => {
+            if let Ok(mut active_scans) = ACTIVE_SCANS.lock() {
+                active_scans.remove(&scan_id);
+            }
+            return Err(format!("Failed to discover directories: {}", e));
+        }
+    };
+    
+    let fresh_repos: HashMap<String, Repository> = match (&cache_service, &existing_cache) {
+        (Some(cache_service), Some(existing_cache)) => {
+            let stale_paths: HashSet<String> = cache_service
+                .find_stale_repositories(existing_cache, &fingerprints)
+                .into_iter()
+                .collect();
+            existing_cache.repositories.iter()
+                .filter(|(repo_path, _)| !stale_paths.contains(*repo_path))
+                .map(|(repo_path, cached_repo)| (repo_path.clone(), cached_repo.repository.clone()))
+                .collect()
+        },
+        _ => HashMap::new(),
+    };
+    println!("♻️  Reusing {} cached entries, analyzing {} directories", 
+             fresh_repos.len(), dirs_to_scan.len() - fresh_repos.len());
     
     // Create a progress callback that emits events
     let app_handle_progress = app.clone();
-    let result = service.scan_directory_with_progress(path, |current_dir, count, total| {
+    let repos = service.analyze_directories_with_progress(dirs_to_scan, &fresh_repos, &rules, &scan, |current_dir, count, total| {
         println!("Progress: Scanning {} ({}/{})", current_dir, count, total);
         let _ = app_handle_progress.emit("scan-progress", serde_json::json!({
+            "scan_id": scan_id,
             "current_directory": current_dir,
             "scanned_count": count,
             "total_count": total
         }));
     }).await;
     
-    match &result {
-        Ok(repos) => {
-            println!("Scan completed successfully! Found {} repositories", repos.len());
-            let is_add_mode = add_mode.unwrap_or(false);
-            
-            // Save to cache (merge with existing if ADD mode)
-            println!("💾 Saving scan results to cache (ADD mode: {})...", is_add_mode);
-            match CacheService::new(app.clone()) {
-                Ok(cache_service) => {
-                    let mut final_repos = repos.clone();
-                    let mut all_scanned_paths = vec![path.to_path_buf()];
-                    let checksums = std::collections::HashMap::new(); // TODO: implement Git HEAD SHA collection
-                    
-                    // If ADD mode, merge with existing cache
-                    if is_add_mode {
-                        if let Ok(Some(existing_cache)) = cache_service.load_cache().await {
-                            println!("🔄 Merging with existing cache ({} repos)...", existing_cache.repositories.len());
-                            let existing_repos = cache_service.extract_repositories(&existing_cache);
-                            
-                            // Merge scanned paths
-                            all_scanned_paths.extend(existing_cache.scanned_paths);
-                            
-                            // Merge repositories, avoiding duplicates by path
-                            let mut existing_paths: std::collections::HashSet<String> = existing_repos.iter().map(|r| r.path.clone()).collect();
-                            let mut merged_repos = existing_repos;
-                            
-                            for new_repo in repos {
-                                if !existing_paths.contains(&new_repo.path) {
-                                    existing_paths.insert(new_repo.path.clone());
-                                    merged_repos.push(new_repo.clone());
-                                } else {
-                                    println!("   Skipping duplicate repository: {}", new_repo.path);
-                                }
-                            }
-                            
-                            final_repos = merged_repos;
-                            println!("✅ Merged result: {} repositories total", final_repos.len());
+    if let Ok(mut active_scans) = ACTIVE_SCANS.lock() {
+        active_scans.remove(&scan_id);
+    }
+    
+    let cancelled = scan.is_cancelled();
+    if cancelled {
+        println!("Scan {} cancelled with {} repositories analyzed", scan_id, repos.len());
+        let _ = app.emit("scan-progress", serde_json::json!({
+            "scan_id": scan_id,
+            "cancelled": true,
+            "found_count": repos.len()
+        }));
+    } else {
+        println!("Scan completed successfully! Found {} repositories", repos.len());
+    }
+    let is_add_mode = add_mode.unwrap_or(false);
+    
+    // Save to cache (merge with existing if ADD mode). Cancelled scans are only
+    // saved when asked to, and their path is then marked as incomplete.
+    println!("💾 Saving scan results to cache (ADD mode: {})...", is_add_mode);
+    match cache_service {
+        Some(_) if cancelled && !save_partial.unwrap_or(false) => {
+            println!("ℹ️  Not saving partial results of cancelled scan");
+        },
+        Some(cache_service) => {
+            // Merge into the cache as it is now, not as it was when the scan started, so
+            // watcher and activity updates made during the scan aren't lost
+            let saved = cache_service.update_cache(|existing_cache| {
+                let mut final_repos = repos.clone();
+                let mut all_scanned_paths = vec![path.to_path_buf()];
+                let mut all_fingerprints = fingerprints;
+                let mut incomplete_paths = Vec::new();
+                
+                // If ADD mode, merge with existing cache
+                if is_add_mode {
+                    if let Some(existing_cache) = &existing_cache {
+                        println!("🔄 Merging with existing cache ({} repos)...", existing_cache.repositories.len());
+                        let existing_repos = cache_service.extract_repositories(existing_cache);
+                        
+                        // Keep fingerprints of existing entries so they stay fresh on the next scan
+                        for (repo_path, cached_repo) in &existing_cache.repositories {
+                            all_fingerprints.entry(repo_path.clone())
+                                .or_insert_with(|| cached_repo.fingerprint());
                         }
-                    }
-                    
-                    let cache_data = cache_service.create_cache_data(
-                        final_repos.clone(),
-                        all_scanned_paths,
-                        checksums,
-                    );
-                    
-                    match cache_service.save_cache(&cache_data).await {
-                        Ok(_) => {
-                            if is_add_mode {
-                                println!("✅ Cache merged and saved with {} repositories", final_repos.len());
+                        
+                        // Merge scanned paths
+                        all_scanned_paths.extend(existing_cache.scanned_paths.iter().cloned());
+                        incomplete_paths = existing_cache.incomplete_paths.clone();
+                        
+                        // Merge repositories, avoiding duplicates by path
+                        let mut existing_paths: HashSet<String> = existing_repos.iter().map(|r| r.path.clone()).collect();
+                        let mut merged_repos = existing_repos;
+                        
+                        for new_repo in &repos {
+                            if !existing_paths.contains(&new_repo.path) {
+                                existing_paths.insert(new_repo.path.clone());
+                                merged_repos.push(new_repo.clone());
                             } else {
-                                println!("✅ Cache replaced and saved with {} repositories", final_repos.len());
+                                println!("   Skipping duplicate repository: {}", new_repo.path);
                             }
-                        },
-                        Err(e) => println!("⚠️  Failed to save cache: {}", e),
+                        }
+                        
+                        final_repos = merged_repos;
+                        println!("✅ Merged result: {} repositories total", final_repos.len());
+                    }
+                }
+                
+                let mut cache_data = cache_service.create_cache_data(
+                    final_repos,
+                    all_scanned_paths,
+                    all_fingerprints,
+                );
+                if let Some(existing_cache) = &existing_cache {
+                    cache_data.carry_over_from(existing_cache);
+                }
+                
+                incomplete_paths.retain(|incomplete_path| incomplete_path != path);
+                if cancelled {
+                    incomplete_paths.push(path.to_path_buf());
+                }
+                cache_data.incomplete_paths = incomplete_paths;
+                Some(cache_data)
+            }).await;
+            
+            match saved {
+                Ok(_) => {
+                    if is_add_mode {
+                        println!("✅ Cache merged and saved");
+                    } else {
+                        println!("✅ Cache replaced and saved with {} repositories", repos.len());
                     }
                 },
-                Err(e) => println!("⚠️  Failed to create cache service: {}", e),
+                Err(e) => println!("⚠️  Failed to save cache: {}", e),
             }
         },
-        Err(e) => println!("Scan failed with error: {}", e),
+        None => println!("⚠️  Skipping cache save, cache service unavailable"),
     }
     
-    result.map_err(|e| e.to_string())
+    // Keep the cache live for the (possibly changed) set of scanned paths
+    if let Err(e) = restart_watcher(&app).await {
+        println!("⚠️  Failed to restart watcher: {}", e);
+    }
+    
+    Ok(repos)
 }
 
 #[tauri::command]
-pub async fn get_directory_stats(directory_path: String) -> Result<DirectoryStats, String> {
+pub async fn cancel_scan(scan_id: String) -> Result<(), String> {
+    let active_scans = ACTIVE_SCANS.lock().map_err(|e| e.to_string())?;
+    
+    match active_scans.get(&scan_id) {
+        Some(scan) => {
+            println!("Cancelling scan {}", scan_id);
+            scan.cancel();
+            Ok(())
+        },
+        None => Err(format!("No running scan with ID {}", scan_id)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_directory_stats(app: AppHandle, directory_path: String) -> Result<DirectoryStats, String> {
     let path = Path::new(&directory_path);
-    let service = REPO_SERVICE.lock().await;
+    let service = Arc::clone(&REPO_SERVICE);
+    let rules = match CacheService::new(app) {
+        Ok(cache_service) => cache_service.load_scan_rules().await,
+        Err(_) => ScanRules::default(),
+    };
     
-    service.get_directory_stats(path)
+    service.get_directory_stats(path, &rules)
         .await
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn get_scan_rules(app: AppHandle) -> Result<ScanRules, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+    match cache_service.load_preferences().await {
+        Ok(preferences) => Ok(preferences.scan_rules),
+        Err(e) => Err(format!("Failed to load preferences: {}", e))
+    }
+}
+
+/// Save the scan rules. They apply to the next scan; the watcher picks them up immediately.
+#[tauri::command]
+pub async fn set_scan_rules(app: AppHandle, rules: ScanRules) -> Result<(), String> {
+    IgnoreAdapter::validate_rules(&rules)?;
+
+    let cache_service = CacheService::new(app.clone())
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+    cache_service.update_preferences(|preferences| preferences.scan_rules = rules).await
+        .map_err(|e| format!("Failed to save preferences: {}", e))?;
+
+    if let Err(e) = restart_watcher(&app).await {
+        println!("⚠️  Failed to restart watcher: {}", e);
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn test_cache_service(app: AppHandle) -> Result<String, String> {
     println!("🧪 Testing cache service...");
@@ -154,7 +320,7 @@ pub async fn test_cache_service(app: AppHandle) -> Result<String, String> {
                     let test_cache = cache_service.create_cache_data(
                         vec![], // Empty repositories for test
                         vec![], // Empty paths
-                        std::collections::HashMap::new(), // Empty checksums
+                        HashMap::new(), // Empty fingerprints
                     );
                     
                     match cache_service.save_cache(&test_cache).await {
@@ -185,13 +351,17 @@ pub async fn test_cache_service(app: AppHandle) -> Result<String, String> {
 pub async fn load_cached_repositories(app: AppHandle) -> Result<Option<Vec<Repository>>, String> {
     println!("🔄 Loading cached repositories...");
     
-    match CacheService::new(app) {
+    match CacheService::new(app.clone()) {
         Ok(cache_service) => {
             match cache_service.load_cache().await {
                 Ok(Some(cache_data)) => {
                     let repos = cache_service.extract_repositories(&cache_data);
                     println!("✅ Loaded {} repositories from cache (last scan: {})", 
                              repos.len(), cache_data.last_scan.format("%Y-%m-%d %H:%M:%S"));
+                    
+                    if let Err(e) = restart_watcher(&app).await {
+                        println!("⚠️  Failed to start watcher: {}", e);
+                    }
                     Ok(Some(repos))
                 },
                 Ok(None) => {
@@ -0,0 +1,68 @@
+// Tauri command handlers for the stash inventory
+use crate::models::*;
+use crate::adapters::GitAdapter;
+use crate::cache::CacheService;
+use crate::services::{StashService, DEFAULT_STASH_AGE_DAYS};
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+static STASH_SERVICE: once_cell::sync::Lazy<StashService> =
+    once_cell::sync::Lazy::new(StashService::new);
+
+#[tauri::command]
+pub async fn get_repo_stashes(repo_path: String) -> Result<Vec<StashInfo>, String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.list_stashes(&path) {
+        Ok(stashes) => Ok(stashes),
+        Err(e) => Err(format!("Failed to list stashes: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn get_old_stashes(app: AppHandle, older_than_days: Option<u32>) -> Result<StashReport, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+    let older_than_days = older_than_days.unwrap_or(DEFAULT_STASH_AGE_DAYS);
+
+    STASH_SERVICE.old_stash_report(&cache_service, older_than_days).await
+}
+
+/// Stashes are addressed by index and SHA; the command fails if `stash@{index}`
+/// is no longer the stash with `sha` (e.g. another stash was pushed or dropped).
+#[tauri::command]
+pub async fn apply_stash(repo_path: String, index: usize, sha: String) -> Result<(), String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.apply_stash(&path, index, &sha) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Failed to apply stash: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn drop_stash(repo_path: String, index: usize, sha: String) -> Result<(), String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.drop_stash(&path, index, &sha) {
+        Ok(()) => {
+            println!("STASHES: Dropped stash@{{{}}} in {} (was {})", index, path.display(), sha);
+            Ok(())
+        },
+        Err(e) => Err(format!("Failed to drop stash: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn get_stash_diff(repo_path: String, index: usize, sha: String) -> Result<String, String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.get_stash_diff(&path, index, &sha) {
+        Ok(diff) => Ok(diff),
+        Err(e) => Err(format!("Failed to get stash diff: {}", e))
+    }
+}
@@ -0,0 +1,56 @@
+// Tauri command handlers for the filesystem watcher
+use crate::cache::CacheService;
+use crate::services::RepositoryWatcher;
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+
+// The active watcher; replacing it drops (and stops) the previous one
+static REPO_WATCHER: once_cell::sync::Lazy<Mutex<Option<RepositoryWatcher>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+/// (Re)start the watcher over every scanned path in the cache
+pub async fn restart_watcher(app: &AppHandle) -> Result<usize, String> {
+    let cache_service = CacheService::new(app.clone()).map_err(|e| e.to_string())?;
+    let cache_data = match cache_service.load_cache().await.map_err(|e| e.to_string())? {
+        Some(cache_data) => cache_data,
+        None => {
+            println!("WATCH: No cache found, nothing to watch");
+            return Ok(0);
+        }
+    };
+
+    let repo_paths: Vec<String> = cache_data.repositories.into_keys().collect();
+    let rules = cache_service.load_scan_rules().await;
+
+    // Walk the scan roots before taking the lock, and off the async workers
+    let watch_set = {
+        let rules = rules.clone();
+        tokio::task::spawn_blocking(move || RepositoryWatcher::watch_set(&cache_data.scanned_paths, &repo_paths, &rules))
+            .await
+            .map_err(|e| format!("Failed to collect directories to watch: {}", e))?
+    };
+
+    let mut active_watcher = REPO_WATCHER.lock().await;
+    // Stop the old watcher before registering new watches
+    *active_watcher = None;
+
+    let watcher = RepositoryWatcher::start(app.clone(), watch_set, rules)
+        .map_err(|e| format!("Failed to start watcher: {}", e))?;
+    let watched_count = watcher.watched_count();
+    *active_watcher = Some(watcher);
+
+    Ok(watched_count)
+}
+
+#[tauri::command]
+pub async fn start_repository_watcher(app: AppHandle) -> Result<usize, String> {
+    restart_watcher(&app).await
+}
+
+#[tauri::command]
+pub async fn stop_repository_watcher() -> Result<(), String> {
+    let mut active_watcher = REPO_WATCHER.lock().await;
+    *active_watcher = None;
+    println!("WATCH: Watcher stopped");
+    Ok(())
+}
@@ -0,0 +1,107 @@
+// Tauri command handlers for staging, discarding and committing changes
+use crate::models::*;
+use crate::adapters::GitAdapter;
+use crate::cache::CacheService;
+use chrono::Utc;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn stage_files(repo_path: String, paths: Vec<String>) -> Result<(), String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.stage_files(&path, &paths) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Failed to stage files: {}", e))
+    }
+}
+
+#[tauri::command]
+pub async fn unstage_files(repo_path: String, paths: Vec<String>) -> Result<(), String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+
+    match git_adapter.unstage_files(&path, &paths) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Failed to unstage files: {}", e))
+    }
+}
+
+/// Stage hunks of `path` as returned by `get_diff` with `staged` false
+#[tauri::command]
+pub async fn stage_hunks(repo_path: String, path: String, hunks: Vec<HunkRange>) -> Result<(), String> {
+    let git_adapter = GitAdapter::new();
+    let repo_path = PathBuf::from(repo_path);
+
+    match git_adapter.stage_hunks(&repo_path, &path, &hunks) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Failed to stage hunks: {}", e))
+    }
+}
+
+/// Unstage hunks of `path` as returned by `get_diff` with `staged` true
+#[tauri::command]
+pub async fn unstage_hunks(repo_path: String, path: String, hunks: Vec<HunkRange>) -> Result<(), String> {
+    let git_adapter = GitAdapter::new();
+    let repo_path = PathBuf::from(repo_path);
+
+    match git_adapter.unstage_hunks(&repo_path, &path, &hunks) {
+        Ok(()) => Ok(()),
+        Err(e) => Err(format!("Failed to unstage hunks: {}", e))
+    }
+}
+
+/// Discard working-tree changes after copying the affected files into the app's
+/// backup directory. The backup is recorded and listed by `get_discard_backups`.
+#[tauri::command]
+pub async fn discard_changes(app: AppHandle, repo_path: String, paths: Vec<String>) -> Result<DiscardBackup, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+
+    let discarded_at = Utc::now();
+    let repo_name = Path::new(&repo_path).file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repository".to_string());
+    let backup_dir = cache_service.get_discard_backup_dir()
+        .join(format!("{}-{}", discarded_at.format("%Y%m%d-%H%M%S%.3f"), repo_name));
+
+    let git_adapter = GitAdapter::new();
+    let files = git_adapter.discard_changes(Path::new(&repo_path), &paths, &backup_dir)
+        .map_err(|e| format!("Failed to discard changes: {}", e))?;
+
+    let backup = DiscardBackup {
+        repo_path,
+        backup_path: backup_dir.to_string_lossy().to_string(),
+        files,
+        discarded_at,
+    };
+    println!("DISCARD: Backed up {} files from {} to {}", backup.files.len(), backup.repo_path, backup.backup_path);
+
+    cache_service.record_discard_backup(backup.clone()).await
+        .map_err(|e| format!("Changes were discarded and backed up to {} but recording the backup failed: {}", backup.backup_path, e))?;
+
+    Ok(backup)
+}
+
+#[tauri::command]
+pub async fn get_discard_backups(app: AppHandle) -> Result<Vec<DiscardBackup>, String> {
+    let cache_service = CacheService::new(app)
+        .map_err(|e| format!("Failed to initialize cache service: {}", e))?;
+
+    cache_service.load_discard_backups().await
+        .map_err(|e| format!("Failed to load discard backups: {}", e))
+}
+
+/// Commit the staged changes. Errors say whether a hook, signing or a missing identity
+/// stopped the commit.
+#[tauri::command]
+pub async fn commit_changes(repo_path: String, message: String) -> Result<CommitResult, String> {
+    let git_adapter = GitAdapter::new();
+    let path = PathBuf::from(repo_path);
+    let timeout = Duration::from_secs(DEFAULT_GIT_COMMAND_TIMEOUT_SECS);
+
+    git_adapter.create_commit(&path, &message, timeout).await
+        .map_err(|e| e.to_string())
+}
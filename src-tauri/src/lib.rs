@@ -0,0 +1,9 @@
+// Repository Manager backend - organized into adapters, services, commands and cache
+pub mod adapters;
+pub mod cache;
+pub mod commands;
+pub mod models;
+pub mod services;
+
+pub use commands::*;
+pub use models::*;
@@ -0,0 +1,63 @@
+// Batch Git operation requests and results
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchOperation {
+    pub operation_type: BatchOperationType,
+    /// Operation-specific parameters, e.g. `message` for Commit or `command` for Custom
+    #[serde(default)]
+    pub parameters: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum BatchOperationType {
+    Pull,
+    Push,
+    Status,
+    Fetch,
+    Commit,
+    /// `git submodule update --init --recursive`
+    SubmoduleUpdate,
+    Custom,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub total_repos: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub results: Vec<BatchOperationResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchOperationResult {
+    pub repo_path: String,
+    pub success: bool,
+    pub output: String,
+    pub error: Option<String>,
+    /// Wall-clock time spent on this repository
+    #[serde(default)]
+    pub elapsed_ms: u64,
+}
+
+/// Per-repository progress emitted while a batch operation runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchProgress {
+    pub batch_id: String,
+    pub repo_path: String,
+    pub state: BatchRepoState,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub elapsed_ms: u64,
+    /// Repositories finished so far (monotonic across events)
+    pub completed_count: usize,
+    pub total_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum BatchRepoState {
+    Started,
+    Succeeded,
+    Failed,
+}
@@ -0,0 +1,73 @@
+// Stale branch detection and cleanup
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Why a local branch is considered stale
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum StaleReason {
+    /// The tip is reachable from the default branch (local or on origin)
+    Merged,
+    /// An upstream is configured but the remote branch no longer exists
+    UpstreamGone,
+    /// No commits within the requested number of days
+    Inactive,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleBranch {
+    pub repo_path: String,
+    pub branch: String,
+    pub tip_sha: String,
+    pub last_commit: Option<DateTime<Utc>>,
+    pub upstream: Option<String>,
+    /// Checked-out branches are reported but can't be deleted
+    pub is_current: bool,
+    pub reasons: Vec<StaleReason>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleBranchReport {
+    pub generated_at: DateTime<Utc>,
+    pub inactive_days: u32,
+    pub repositories_analyzed: usize,
+    pub failed_repositories: Vec<String>,
+    pub branches: Vec<StaleBranch>,
+}
+
+/// A branch selected for deletion. `tip_sha` is the tip the user saw in the report;
+/// the branch is left alone if it has moved since.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchDeletionRequest {
+    pub repo_path: String,
+    pub branch: String,
+    pub tip_sha: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchCleanupResult {
+    pub dry_run: bool,
+    pub deleted: usize,
+    pub failed: usize,
+    pub results: Vec<BranchDeletionResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchDeletionResult {
+    pub repo_path: String,
+    pub branch: String,
+    pub success: bool,
+    /// Tip the branch was deleted at, or would be on a dry run
+    #[serde(default)]
+    pub tip_sha: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A deleted branch tip, kept so the branch can be restored
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedBranchRecord {
+    pub repo_path: String,
+    pub branch: String,
+    pub tip_sha: String,
+    pub upstream: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+}
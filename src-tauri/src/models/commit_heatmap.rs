@@ -0,0 +1,66 @@
+// GitHub-style commit heatmap data
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Weeks shown in the heatmap grid, excluding the current (partial) week
+pub const HEATMAP_WEEKS: i64 = 52;
+
+/// Daily commit counts laid out as a grid of weeks (columns), Sunday through Saturday
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitHeatmap {
+    /// The Sunday the first week starts on
+    pub start_date: NaiveDate,
+    /// Today; the last week stops here
+    pub end_date: NaiveDate,
+    /// `weeks[w][d]` is the commit count on `start_date + 7w + d` days
+    pub weeks: Vec<Vec<u32>>,
+    pub total_commits: u32,
+    pub max_daily_commits: u32,
+    /// Repositories that contributed to the counts
+    pub repositories: usize,
+    /// Repositories with more commits in the window than were walked, so their counts are partial
+    pub truncated_repositories: Vec<String>,
+}
+
+impl CommitHeatmap {
+    /// First day the grid ending on `today` covers
+    pub fn grid_start(today: NaiveDate) -> NaiveDate {
+        let year_ago = today - Duration::weeks(HEATMAP_WEEKS);
+        year_ago - Duration::days(year_ago.weekday().num_days_from_sunday() as i64)
+    }
+
+    /// Lay out `daily_commits` for the grid ending on `today`; days outside the grid are ignored
+    pub fn from_daily_counts(daily_commits: &BTreeMap<NaiveDate, u32>, today: NaiveDate, repositories: usize) -> Self {
+        let start_date = Self::grid_start(today);
+        let mut weeks: Vec<Vec<u32>> = Vec::new();
+        let mut total_commits = 0;
+        let mut max_daily_commits = 0;
+
+        let mut date = start_date;
+        while date <= today {
+            if date.weekday().num_days_from_sunday() == 0 {
+                weeks.push(Vec::with_capacity(7));
+            }
+
+            let count = daily_commits.get(&date).copied().unwrap_or(0);
+            total_commits += count;
+            max_daily_commits = max_daily_commits.max(count);
+            if let Some(week) = weeks.last_mut() {
+                week.push(count);
+            }
+
+            date += Duration::days(1);
+        }
+
+        Self {
+            start_date,
+            end_date: today,
+            weeks,
+            total_commits,
+            max_daily_commits,
+            repositories,
+            truncated_repositories: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+// Per-author contribution report across the cached repositories
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// One author's commits in a single repository, as collected from its history
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthorCommitStats {
+    pub name: String,
+    /// Lowercased, after mailmap resolution; empty when the commits carry no email
+    pub email: String,
+    pub commits: u32,
+    pub lines_added: usize,
+    pub lines_deleted: usize,
+    /// Calendar days (in the author's timezone) with at least one commit
+    pub active_days: BTreeSet<NaiveDate>,
+    pub last_commit_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContributionReport {
+    pub window_days: u32,
+    pub generated_at: DateTime<Utc>,
+    pub repositories_analyzed: usize,
+    /// Repositories whose history couldn't be read
+    pub failed_repositories: Vec<String>,
+    /// Repositories with more commits in the window than were walked, so their counts are partial
+    pub truncated_repositories: Vec<String>,
+    /// Sorted by commit count, most active first
+    pub authors: Vec<AuthorContribution>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorContribution {
+    pub name: String,
+    pub email: String,
+    pub commits: u32,
+    pub lines_added: usize,
+    pub lines_deleted: usize,
+    /// Distinct days with commits in any repository
+    pub active_days: usize,
+    pub last_commit_date: Option<DateTime<Utc>>,
+    /// Where the author was active, most commits first
+    pub repositories: Vec<AuthorRepoContribution>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorRepoContribution {
+    pub repo_name: String,
+    pub repo_path: String,
+    pub commits: u32,
+    pub lines_added: usize,
+    pub lines_deleted: usize,
+    pub active_days: usize,
+}
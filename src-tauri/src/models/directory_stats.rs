@@ -0,0 +1,19 @@
+// Aggregate statistics for a scanned directory
+use super::repository::Repository;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryStats {
+    pub total_directories: u32,
+    pub git_repositories: u32,
+    pub non_git_directories: u32,
+    pub repositories_with_changes: u32,
+    pub repositories_with_remotes: u32,
+    pub total_size_mb: f64,
+    pub largest_repos: Vec<Repository>,
+    pub most_active_repos: Vec<Repository>,
+    pub repos_needing_attention: Vec<Repository>,
+    /// Repositories on a feature branch that has fallen behind the default branch, furthest behind first
+    #[serde(default)]
+    pub repos_behind_default: Vec<Repository>,
+}
@@ -0,0 +1,59 @@
+// Structured diff of a single file, for reviewing working-tree and staged changes
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: String,
+    /// Source path when the file was renamed or copied
+    pub old_path: Option<String>,
+    pub status: FileChangeStatus,
+    /// Binary files (and files too large to diff) have no hunks
+    pub is_binary: bool,
+    pub lines_added: usize,
+    pub lines_deleted: usize,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum FileChangeStatus {
+    Unmodified,
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Copied,
+    TypeChange,
+    Untracked,
+    Conflicted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffHunk {
+    /// The `@@ -a,b +c,d @@ ...` line
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Line number in the old file; None for added lines
+    pub old_lineno: Option<u32>,
+    /// Line number in the new file; None for removed lines
+    pub new_lineno: Option<u32>,
+    /// Line content without the trailing newline
+    pub content: String,
+    /// The line is the last in its file and has no newline at the end
+    pub missing_newline: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
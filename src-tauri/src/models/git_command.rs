@@ -0,0 +1,232 @@
+// Sandboxed git command policy and results
+use serde::{Deserialize, Serialize};
+
+/// Seconds a user-supplied git command may run before it is killed
+pub const DEFAULT_GIT_COMMAND_TIMEOUT_SECS: u64 = 120;
+
+/// Which user-supplied git commands may run, and which need explicit confirmation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitCommandPolicy {
+    /// Subcommands that may run at all
+    pub allowed_subcommands: Vec<String>,
+    /// Invocations that are never run, even when confirmed
+    pub denied: Vec<GitCommandRule>,
+    /// Destructive invocations that only run when the caller confirms them
+    pub confirm_required: Vec<GitCommandRule>,
+    pub timeout_secs: u64,
+}
+
+/// Matches a git invocation by subcommand ("*" for any) and, optionally, its arguments
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GitCommandRule {
+    pub subcommand: String,
+    /// The rule matches when any of these arguments is present; empty matches every invocation.
+    /// Short flags also match inside bundles (`-f` matches `-fdx`), long flags match `--flag=value`
+    /// and the abbreviations git accepts (`--forc`), and a trailing `*` matches by prefix
+    /// (`+*` matches the refspec `+main`).
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitCommandResult {
+    pub command: Vec<String>,
+    pub success: bool,
+    /// None when the process was killed (timeout or signal)
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+    pub elapsed_ms: u64,
+}
+
+impl GitCommandPolicy {
+    /// Check `args` (without the leading "git") against the policy
+    pub fn check(&self, args: &[String], confirmed: bool) -> Result<(), String> {
+        let subcommand = match args.first() {
+            Some(subcommand) => subcommand,
+            None => return Err("No git command given".to_string()),
+        };
+
+        // Global options such as `-c core.sshCommand=...` could run arbitrary programs
+        if subcommand.starts_with('-') {
+            return Err(format!("Global git options are not allowed: {}", subcommand));
+        }
+
+        if !self.allowed_subcommands.iter().any(|allowed| allowed == subcommand) {
+            return Err(format!("git {} is not in the allowed command list", subcommand));
+        }
+
+        if let Some(rule) = self.denied.iter().find(|rule| rule.matches(args)) {
+            return Err(format!("git {} is blocked by policy", rule.describe()));
+        }
+
+        if !confirmed {
+            if let Some(rule) = self.confirm_required.iter().find(|rule| rule.matches(args)) {
+                return Err(format!("Confirmation required: git {} can discard work", rule.describe()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl GitCommandRule {
+    fn new(subcommand: &str, args: &[&str]) -> Self {
+        Self {
+            subcommand: subcommand.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    pub fn matches(&self, args: &[String]) -> bool {
+        let (subcommand, rest) = match args.split_first() {
+            Some(split) => split,
+            None => return false,
+        };
+
+        if self.subcommand != "*" && &self.subcommand != subcommand {
+            return false;
+        }
+
+        self.args.is_empty() || rest.iter().any(|arg| self.args.iter().any(|pattern| Self::arg_matches(arg, pattern)))
+    }
+
+    fn arg_matches(arg: &str, pattern: &str) -> bool {
+        if arg == pattern {
+            return true;
+        }
+
+        if let Some(prefix) = pattern.strip_suffix('*') {
+            return arg.starts_with(prefix);
+        }
+
+        if let Some(long) = pattern.strip_prefix("--") {
+            // git takes any unambiguous prefix of a long option, so `--forc` and `--upload-pa=sh`
+            // count as `--force` and `--upload-pack`; matching every prefix errs on the safe side
+            let name = arg.strip_prefix("--").map(|a| a.split('=').next().unwrap_or(a));
+            return name.is_some_and(|name| !name.is_empty() && long.starts_with(name));
+        }
+
+        match pattern.strip_prefix('-') {
+            Some(short) if short.len() == 1 && arg.starts_with('-') && !arg.starts_with("--") => arg[1..].contains(short),
+            _ => false,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self.args.as_slice() {
+            [] => self.subcommand.clone(),
+            args => format!("{} {}", self.subcommand, args.join("/")),
+        }
+    }
+}
+
+impl Default for GitCommandPolicy {
+    fn default() -> Self {
+        let allowed_subcommands = [
+            "status", "log", "show", "diff", "branch", "tag", "remote", "fetch", "pull", "push",
+            "add", "rm", "mv", "restore", "reset", "commit", "checkout", "switch", "merge",
+            "rebase", "cherry-pick", "revert", "stash", "clean", "gc", "describe", "shortlog",
+            "blame", "ls-files", "rev-parse", "reflog", "worktree",
+        ];
+
+        Self {
+            allowed_subcommands: allowed_subcommands.iter().map(|s| s.to_string()).collect(),
+            denied: vec![
+                // Options that make git run an arbitrary program
+                GitCommandRule::new("*", &["--upload-pack", "--receive-pack", "--exec"]),
+                // Writes to any file on disk
+                GitCommandRule::new("*", &["--output"]),
+                // Short form of --exec
+                GitCommandRule::new("rebase", &["-x"]),
+            ],
+            confirm_required: vec![
+                // `+ref` force-pushes and `:ref` deletes the remote ref
+                GitCommandRule::new("push", &["--force", "-f", "--force-with-lease", "--mirror", "--delete", "-d", "--prune", "+*", ":*"]),
+                GitCommandRule::new("clean", &["--force", "-f"]),
+                GitCommandRule::new("reset", &["--hard", "--merge", "--keep"]),
+                // `checkout <path>` discards changes and can't be told apart from `checkout <branch>`
+                // without the repository; `switch` changes branches without confirmation
+                GitCommandRule::new("checkout", &[]),
+                GitCommandRule::new("switch", &["--force", "-f", "--discard-changes"]),
+                GitCommandRule::new("restore", &[]),
+                GitCommandRule::new("rm", &["--force", "-f"]),
+                // `-f`, `-M` and `-C` overwrite an existing branch
+                GitCommandRule::new("branch", &["-D", "--delete", "-d", "--force", "-f", "-M", "-C"]),
+                GitCommandRule::new("tag", &["--force", "-f", "--delete", "-d"]),
+                // `worktree remove --force` drops the worktree's uncommitted changes
+                GitCommandRule::new("worktree", &["--force", "-f"]),
+                GitCommandRule::new("stash", &["drop", "clear"]),
+                GitCommandRule::new("rebase", &[]),
+                GitCommandRule::new("gc", &["--prune"]),
+                GitCommandRule::new("reflog", &["expire", "delete"]),
+            ],
+            timeout_secs: DEFAULT_GIT_COMMAND_TIMEOUT_SECS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &str) -> Vec<String> {
+        command.split_whitespace().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn arg_matches_flags() {
+        assert!(GitCommandRule::arg_matches("--force", "--force"));
+        assert!(GitCommandRule::arg_matches("--output=/tmp/x", "--output"));
+        assert!(!GitCommandRule::arg_matches("--force-with-lease", "--force"));
+        assert!(GitCommandRule::arg_matches("--forc", "--force"));
+        assert!(GitCommandRule::arg_matches("--upload-pa=sh", "--upload-pack"));
+        assert!(!GitCommandRule::arg_matches("--", "--force"));
+        assert!(!GitCommandRule::arg_matches("--forced", "--force"));
+        assert!(GitCommandRule::arg_matches("-fdx", "-f"));
+        assert!(!GitCommandRule::arg_matches("--fix", "-f"));
+        assert!(!GitCommandRule::arg_matches("feature", "-f"));
+        assert!(GitCommandRule::arg_matches("+main", "+*"));
+        assert!(GitCommandRule::arg_matches(":main", ":*"));
+        assert!(!GitCommandRule::arg_matches("main:main", ":*"));
+    }
+
+    #[test]
+    fn rule_matches_subcommand_and_args() {
+        let rule = GitCommandRule::new("push", &["--force", "+*"]);
+        assert!(rule.matches(&args("push origin --force")));
+        assert!(rule.matches(&args("push origin +main")));
+        assert!(!rule.matches(&args("push origin main")));
+        assert!(!rule.matches(&args("fetch --force")));
+        assert!(!rule.matches(&[]));
+
+        assert!(GitCommandRule::new("rebase", &[]).matches(&args("rebase main")));
+        assert!(GitCommandRule::new("*", &["--output"]).matches(&args("diff --output=x")));
+    }
+
+    #[test]
+    fn default_policy() {
+        let policy = GitCommandPolicy::default();
+        let check = |command: &str, confirmed: bool| policy.check(&args(command), confirmed);
+
+        assert!(check("status", false).is_ok());
+        assert!(check("push origin main", false).is_ok());
+        assert!(check("switch feature", false).is_ok());
+        assert!(check("branch feature", false).is_ok());
+        assert!(check("rebase -X ours main", true).is_ok());
+
+        for denied in ["submodule foreach rm -rf /", "log --output=/etc/x", "diff --output /tmp/x", "-c core.pager=sh status", "fetch --upload-pack=sh",
+                       "fetch --upload-pa=sh", "push --rec=sh", "log --outp=/etc/x", "rebase -x sh main", "rebase -ix sh main"] {
+            assert!(check(denied, true).is_err(), "{} was allowed", denied);
+        }
+
+        for destructive in ["push origin +main", "push origin :main", "push -f", "checkout .", "checkout src/lib.rs", "switch -f main", "switch --discard-changes main", "reset --hard", "clean -fdx",
+                            "push --forc", "reset --har", "clean --forc -dx", "push --prune origin", "rm -f x", "rm --force x",
+                            "branch -f main HEAD~1", "branch -M old new", "branch -C old new", "tag -f v1", "tag -d v1",
+                            "worktree remove --force ../wt"] {
+            assert!(check(destructive, false).is_err(), "{} ran without confirmation", destructive);
+            assert!(check(destructive, true).is_ok(), "{} was denied", destructive);
+        }
+    }
+}
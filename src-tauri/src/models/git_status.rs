@@ -0,0 +1,95 @@
+// Git status, remote and branch data structures
+use chrono::{DateTime, Utc};
+use super::file_diff::FileChangeStatus;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitStatus {
+    pub is_clean: bool,
+    pub staged_files: Vec<String>,
+    pub unstaged_files: Vec<String>,
+    pub untracked_files: Vec<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub current_branch: Option<String>,
+    pub tracking_branch: Option<String>,
+    /// Merge, rebase, etc. that was started but not finished
+    #[serde(default)]
+    pub operation_in_progress: Option<GitOperation>,
+    /// Paths with unresolved conflicts
+    #[serde(default)]
+    pub conflicted_files: Vec<String>,
+    /// Every changed, untracked, conflicted or ignored path with its full status; only
+    /// filled in by the detailed status
+    #[serde(default)]
+    pub files: Vec<FileStatusEntry>,
+}
+
+/// Status of one path. A path can be changed in both the index and the working tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStatusEntry {
+    pub path: String,
+    /// Source path when the file was renamed
+    pub old_path: Option<String>,
+    /// Change staged in the index relative to HEAD
+    pub index_status: Option<FileChangeStatus>,
+    /// Change in the working tree relative to the index
+    pub worktree_status: Option<FileChangeStatus>,
+    pub is_conflicted: bool,
+    /// Ignored directories are reported once, not per file
+    pub is_ignored: bool,
+    /// Size of the file in the working tree; None for deleted files and directories
+    pub size_bytes: Option<u64>,
+    /// Set when the path is a submodule
+    pub submodule: Option<SubmoduleChanges>,
+}
+
+/// What changed inside a submodule, as `git status` describes it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct SubmoduleChanges {
+    /// The checked-out commit differs from the one recorded in the parent
+    pub new_commits: bool,
+    pub modified_content: bool,
+    pub untracked_content: bool,
+}
+
+/// A multi-step Git operation left in progress in the working tree
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum GitOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
+/// How HEAD relates to the repository's default branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DefaultBranchStatus {
+    pub name: String,
+    pub is_current: bool,
+    /// Commits on HEAD not on the default branch
+    pub ahead: usize,
+    /// Commits on the default branch not on HEAD
+    pub behind: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteInfo {
+    pub name: String,
+    pub url: String,
+    pub fetch_url: Option<String>,
+    pub push_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchInfo {
+    pub name: String,
+    pub is_current: bool,
+    pub is_remote: bool,
+    pub upstream: Option<String>,
+    pub last_commit: Option<DateTime<Utc>>,
+    pub ahead: usize,
+    pub behind: usize,
+}
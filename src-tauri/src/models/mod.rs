@@ -3,9 +3,33 @@ pub mod repository;
 pub mod git_status;
 pub mod batch_operations;
 pub mod directory_stats;
+pub mod git_command;
+pub mod repo_activity;
+pub mod contribution_report;
+pub mod commit_heatmap;
+pub mod branch_cleanup;
+pub mod stash;
+pub mod file_diff;
+pub mod working_tree;
+pub mod submodule;
+pub mod scan_rules;
+pub mod worktree;
+pub mod sub_project;
 
 // Re-export all types
 pub use repository::*;
 pub use git_status::*;
 pub use batch_operations::*;
-pub use directory_stats::*;
\ No newline at end of file
+pub use directory_stats::*;
+pub use git_command::*;
+pub use repo_activity::*;
+pub use contribution_report::*;
+pub use commit_heatmap::*;
+pub use branch_cleanup::*;
+pub use stash::*;
+pub use file_diff::*;
+pub use working_tree::*;
+pub use submodule::*;
+pub use scan_rules::*;
+pub use worktree::*;
+pub use sub_project::*;
\ No newline at end of file
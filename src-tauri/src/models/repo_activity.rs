@@ -0,0 +1,23 @@
+// Commit activity analytics for a single repository
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Recent commit activity. The `*_last_month` fields cover the requested window
+/// (`window_days`, 30 days by default); `commits_last_week` always covers 7 days.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RepoActivity {
+    /// Commits reachable from HEAD, capped at `COMMIT_COUNT_LIMIT`
+    pub total_commits: u32,
+    pub commits_last_week: u32,
+    pub commits_last_month: u32,
+    pub last_commit_date: Option<DateTime<Utc>>,
+    pub authors_last_month: Vec<String>,
+    pub files_changed_last_month: usize,
+    pub lines_added_last_month: usize,
+    pub lines_deleted_last_month: usize,
+    #[serde(default)]
+    pub window_days: u32,
+    /// The window held more commits than `ACTIVITY_COMMIT_LIMIT`, so the counts are partial
+    #[serde(default)]
+    pub truncated: bool,
+}
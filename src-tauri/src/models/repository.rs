@@ -0,0 +1,87 @@
+// Repository data structures shared between the scanner, cache and frontend
+use super::git_status::GitOperation;
+use super::sub_project::SubProject;
+use super::submodule::SubmoduleInfo;
+use super::worktree::WorktreeInfo;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Repository {
+    pub name: String,
+    pub path: String,
+    pub is_git_repo: bool,
+    pub has_uncommitted_changes: bool,
+    pub current_branch: Option<String>,
+    pub remotes: Vec<String>,
+    pub last_commit_date: Option<DateTime<Utc>>,
+    pub last_activity: Option<DateTime<Utc>>,
+    pub status: RepoStatus,
+    pub size_mb: f64,
+    pub commit_count: Option<u32>,
+    /// History is longer than `COMMIT_COUNT_LIMIT`, so `commit_count` is a lower bound
+    #[serde(default)]
+    pub commit_count_truncated: bool,
+    pub primary_language: Option<String>,
+    pub total_lines: usize,
+    pub code_lines: usize,
+    /// Commits on the current branch not yet on its upstream
+    #[serde(default)]
+    pub ahead: usize,
+    /// Commits on the upstream not yet on the current branch
+    #[serde(default)]
+    pub behind: usize,
+    /// The default branch (`origin/HEAD`, or main/master/develop/trunk)
+    #[serde(default)]
+    pub default_branch: Option<String>,
+    #[serde(default)]
+    pub is_default_branch: bool,
+    /// Commits on the current branch not on the default branch
+    #[serde(default)]
+    pub ahead_of_default: usize,
+    /// Commits on the default branch not on the current branch
+    #[serde(default)]
+    pub behind_default: usize,
+    /// Merge, rebase, etc. that was started but not finished
+    #[serde(default)]
+    pub operation_in_progress: Option<GitOperation>,
+    /// Paths with unresolved conflicts
+    #[serde(default)]
+    pub conflicted_files: Vec<String>,
+    #[serde(default)]
+    pub stash_count: usize,
+    /// Submodules are reported here instead of as separate repositories
+    #[serde(default)]
+    pub submodules: Vec<SubmoduleInfo>,
+    /// None for directories that aren't Git repositories
+    #[serde(default)]
+    pub repo_kind: Option<RepoKind>,
+    /// Linked worktrees of this repository; each is also reported as its own entry
+    #[serde(default)]
+    pub worktrees: Vec<WorktreeInfo>,
+    /// Workspace members, when sub-project discovery is enabled in the scan rules
+    #[serde(default)]
+    pub sub_projects: Vec<SubProject>,
+}
+
+/// How a Git repository is laid out on disk
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RepoKind {
+    /// Working tree with its own `.git` directory
+    Normal,
+    /// Linked worktree (`git worktree add`) of the repository at `main_repo`
+    Worktree { main_repo: String },
+    /// Repository without a working tree, e.g. a `*.git` mirror
+    Bare,
+    /// Submodule checkout whose Git directory lives inside `parent_repo`'s
+    Submodule { parent_repo: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RepoStatus {
+    Clean,
+    Dirty,
+    Untracked,
+    NoGit,
+    Error(String),
+}
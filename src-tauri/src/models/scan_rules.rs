@@ -0,0 +1,122 @@
+// User-editable rules deciding which directories a scan looks at
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory levels below the scan root that are searched by default
+pub const DEFAULT_SCAN_DEPTH: usize = 3;
+
+/// How deep a scan searches below its root. Scans never descend into a Git repository
+/// once it is found, whatever the depth.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ScanDepth {
+    /// Search at most this many levels below the root
+    Levels(usize),
+    /// Search without a depth limit, stopping only at repositories and excluded directories
+    UntilRepository,
+}
+
+impl Default for ScanDepth {
+    fn default() -> Self {
+        ScanDepth::Levels(DEFAULT_SCAN_DEPTH)
+    }
+}
+
+impl ScanDepth {
+    /// Depth limit for the directory walker; None means unlimited
+    pub fn max_depth(&self) -> Option<usize> {
+        match self {
+            ScanDepth::Levels(levels) => Some(*levels),
+            ScanDepth::UntilRepository => None,
+        }
+    }
+}
+
+/// Which directories are scanned. Patterns use .gitignore syntax and match case-insensitively:
+/// a bare name (`node_modules`) matches at any depth, a pattern with a slash is anchored
+/// (to the filesystem root for global rules, to the scan root for per-root rules).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScanRules {
+    /// Directories that are neither reported nor descended into
+    pub exclude: Vec<String>,
+    /// Directories that aren't reported as projects themselves but are still descended
+    /// into, e.g. source folders of unversioned projects. Git repositories always are reported.
+    pub not_projects: Vec<String>,
+    /// Exceptions to `exclude` and `not_projects`
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Directory names or absolute paths that no rule applies to
+    #[serde(default)]
+    pub never_skip: Vec<String>,
+    /// Rules for specific scan roots; they take precedence over the global ones
+    #[serde(default)]
+    pub root_overrides: Vec<ScanRootRules>,
+    /// Depth used for scan roots without a depth override
+    #[serde(default)]
+    pub depth: ScanDepth,
+    /// Report the workspace members of repositories (Cargo, npm/pnpm/yarn, go.work,
+    /// Maven) as sub-projects with their own language and line stats
+    #[serde(default)]
+    pub sub_projects: bool,
+}
+
+/// Extra rules for directories under `root`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScanRootRules {
+    pub root: PathBuf,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub not_projects: Vec<String>,
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Depth for scans of `root` or a directory inside it
+    #[serde(default)]
+    pub depth: Option<ScanDepth>,
+}
+
+impl ScanRules {
+    /// Depth to scan `base_path` with: the override of the most specific root containing it,
+    /// or the global depth
+    pub fn depth_for(&self, base_path: &Path) -> ScanDepth {
+        self.root_overrides.iter()
+            .filter(|root_rules| root_rules.depth.is_some() && base_path.starts_with(&root_rules.root))
+            .max_by_key(|root_rules| root_rules.root.components().count())
+            .and_then(|root_rules| root_rules.depth)
+            .unwrap_or(self.depth)
+    }
+}
+
+impl Default for ScanRules {
+    fn default() -> Self {
+        let to_strings = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect();
+
+        Self {
+            exclude: to_strings(&[
+                ".git",
+                // Package managers & dependencies
+                "node_modules", "vendor", "target", "dist", "out",
+                // Cache/temp
+                "cache", ".cache", "tmp", "temp", "logs",
+                // Tool homes and caches full of package.json/Cargo.toml that aren't projects;
+                // other hidden directories are walked. Opt back in with `include` or `never_skip`.
+                ".vscode", ".vscode-server", ".cargo", ".rustup", ".npm", ".nvm", ".yarn", ".pnpm-store",
+                ".m2", ".gradle", ".local", "node_modules/@*",
+                // OS specific
+                "System Volume Information", "$RECYCLE.BIN", ".Trash",
+            ]),
+            not_projects: to_strings(&[
+                // Hidden directories and npm scoped packages
+                ".*", "@*",
+                // Source subdirectories (not root projects)
+                "src", "lib", "libs", "components", "utils", "helpers", "build",
+                // Test directories
+                "tests", "test", "__tests__", "spec", "specs",
+            ]),
+            include: Vec::new(),
+            never_skip: Vec::new(),
+            root_overrides: Vec::new(),
+            depth: ScanDepth::default(),
+            sub_projects: false,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+// Stash inventory data structures
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StashInfo {
+    pub repo_path: String,
+    /// Position in the stash list (`stash@{index}`)
+    pub index: usize,
+    /// Commit ID of the stash; indexes shift as stashes are dropped, this doesn't
+    pub sha: String,
+    pub message: String,
+    /// Branch the stash was created on, parsed from the stash message
+    pub branch: Option<String>,
+    pub date: Option<DateTime<Utc>>,
+    /// Files changed in the stash, including stashed untracked files
+    pub file_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StashReport {
+    pub generated_at: DateTime<Utc>,
+    pub older_than_days: u32,
+    pub repositories_analyzed: usize,
+    pub failed_repositories: Vec<String>,
+    /// Oldest first
+    pub stashes: Vec<StashInfo>,
+}
@@ -0,0 +1,43 @@
+// Monorepo sub-project data structures
+use serde::{Deserialize, Serialize};
+
+/// Workspace manifest a sub-project is declared in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum WorkspaceKind {
+    /// `[workspace] members` in Cargo.toml
+    Cargo,
+    /// `workspaces` in package.json, used by npm and yarn
+    Npm,
+    /// `packages` in pnpm-workspace.yaml
+    Pnpm,
+    /// `use` directives in go.work
+    Go,
+    /// `<modules>` in pom.xml
+    Maven,
+}
+
+impl WorkspaceKind {
+    /// Manifest every member of this kind of workspace has
+    pub fn member_manifest(&self) -> &'static str {
+        match self {
+            WorkspaceKind::Cargo => "Cargo.toml",
+            WorkspaceKind::Npm | WorkspaceKind::Pnpm => "package.json",
+            WorkspaceKind::Go => "go.mod",
+            WorkspaceKind::Maven => "pom.xml",
+        }
+    }
+}
+
+/// Workspace member inside a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubProject {
+    /// Package, module or artifact name from the member's manifest, else its directory name
+    pub name: String,
+    pub path: String,
+    /// Path relative to the repository root
+    pub relative_path: String,
+    pub workspace: WorkspaceKind,
+    pub primary_language: Option<String>,
+    pub total_lines: usize,
+    pub code_lines: usize,
+}
@@ -0,0 +1,18 @@
+// Submodule data structures
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmoduleInfo {
+    pub name: String,
+    /// Path relative to the parent repository's root
+    pub path: String,
+    pub url: Option<String>,
+    /// Commit the parent repository records for the submodule
+    pub recorded_sha: Option<String>,
+    /// Commit checked out in the submodule's working tree; None when not checked out
+    pub checked_out_sha: Option<String>,
+    /// Registered in the parent's config and checked out
+    pub is_initialized: bool,
+    /// Modified or untracked files inside the submodule
+    pub is_dirty: bool,
+}
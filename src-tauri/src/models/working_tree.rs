@@ -0,0 +1,33 @@
+// Staging, discarding and committing changes from the app
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Identifies a hunk returned by `get_diff`; a `DiffHunk` deserializes into this directly.
+/// Hunk operations fail if the file has changed so that the hunk no longer exists.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct HunkRange {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitResult {
+    pub sha: String,
+    pub summary: String,
+    pub branch: Option<String>,
+    /// Output of the commit command, including any hook output
+    pub output: String,
+}
+
+/// Working-tree files copied aside before their changes were discarded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscardBackup {
+    pub repo_path: String,
+    /// Directory holding the copies, laid out like the repository
+    pub backup_path: String,
+    /// Paths relative to the repository root
+    pub files: Vec<String>,
+    pub discarded_at: DateTime<Utc>,
+}
@@ -0,0 +1,14 @@
+// Linked worktree data structures
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeInfo {
+    pub name: String,
+    pub path: String,
+    /// Branch checked out in the worktree; None when HEAD is detached
+    pub branch: Option<String>,
+    pub head_sha: Option<String>,
+    pub is_locked: bool,
+    /// The worktree directory is gone; `git worktree prune` would drop it
+    pub is_missing: bool,
+}
@@ -0,0 +1,295 @@
+// Activity service - commit analytics backed by the repository cache
+use crate::models::*;
+use crate::adapters::*;
+use crate::cache::{CacheService, CachedActivity, CachedHeatmap};
+use chrono::{NaiveDate, Utc};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Activity window used when the caller doesn't specify one
+pub const DEFAULT_ACTIVITY_DAYS: u32 = 30;
+
+/// Repositories whose history is walked at once for workspace-wide reports
+const REPORT_WORKERS: usize = 4;
+
+pub struct ActivityService {
+    git_adapter: GitAdapter,
+}
+
+impl Default for ActivityService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActivityService {
+    pub fn new() -> Self {
+        Self {
+            git_adapter: GitAdapter::new(),
+        }
+    }
+
+    /// Get commit activity for the last `days` days, reusing the cached result while HEAD
+    /// hasn't moved. Fresh results are stored on the repository's cache entry.
+    pub async fn get_activity(&self, cache_service: Option<&CacheService>, repo_path: &str, days: u32) -> Result<RepoActivity, String> {
+        let path = PathBuf::from(repo_path);
+        let head_sha = self.git_adapter.get_head_sha(&path);
+
+        let mut cached_repo = match cache_service {
+            Some(cache_service) => cache_service.load_cache().await
+                .ok()
+                .flatten()
+                .and_then(|mut cache_data| cache_data.repositories.remove(repo_path)),
+            None => None,
+        };
+
+        if let (Some(cached_activity), Some(sha)) = (cached_repo.as_ref().and_then(|c| c.activity.as_ref()), &head_sha) {
+            if cached_activity.is_current(sha, days) {
+                println!("ACTIVITY: Using cached activity for {}", repo_path);
+                return Ok(cached_activity.activity.clone());
+            }
+        }
+
+        // The revwalk and diffs are blocking git2 calls
+        let activity = tokio::task::spawn_blocking(move || GitAdapter::new().get_activity(&path, days))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+
+        if let (Some(cache_service), Some(cached_repo), Some(sha)) = (cache_service, cached_repo.as_mut(), head_sha) {
+            cached_repo.activity = Some(CachedActivity::new(sha, days, activity.clone()));
+            if let Err(e) = cache_service.update_entries(vec![cached_repo.clone()], &[]).await {
+                println!("ACTIVITY: Failed to cache activity for {}: {}", repo_path, e);
+            }
+        }
+
+        Ok(activity)
+    }
+
+    /// Aggregate per-author contributions over the last `days` days across every cached
+    /// Git repository. Authors are matched by (mailmapped) email across repositories.
+    pub async fn contribution_report(&self, cache_service: &CacheService, days: u32) -> Result<ContributionReport, String> {
+        let cache_data = cache_service.load_cache().await
+            .map_err(|e| format!("Failed to load cache: {}", e))?
+            .ok_or("No cached repositories, scan a directory first")?;
+
+        let repos: HashMap<String, Repository> = cache_data.repositories.into_iter()
+            .filter(|(_, cached_repo)| cached_repo.repository.is_git_repo)
+            .map(|(repo_path, cached_repo)| (repo_path, cached_repo.repository))
+            .collect();
+        println!("REPORT: Collecting contributions for {} repositories over {} days", repos.len(), days);
+
+        let results = Self::walk_repositories(repos.keys().cloned().collect(), move |git_adapter, path| {
+            git_adapter.get_author_stats(path, days)
+        }).await;
+
+        let mut report = ContributionReport {
+            window_days: days,
+            generated_at: Utc::now(),
+            repositories_analyzed: 0,
+            failed_repositories: Vec::new(),
+            truncated_repositories: Vec::new(),
+            authors: Vec::new(),
+        };
+        let mut authors: HashMap<String, (AuthorContribution, BTreeSet<NaiveDate>)> = HashMap::new();
+
+        for (repo_path, result) in results {
+            let Some(repo) = repos.get(&repo_path) else {
+                continue;
+            };
+
+            let (repo_authors, truncated) = match result {
+                Ok(repo_result) => repo_result,
+                Err(e) => {
+                    println!("REPORT: Failed to read history of {}: {}", repo.path, e);
+                    report.failed_repositories.push(repo.path.clone());
+                    continue;
+                }
+            };
+
+            report.repositories_analyzed += 1;
+            if truncated {
+                report.truncated_repositories.push(repo.path.clone());
+            }
+
+            for stats in repo_authors {
+                let key = if stats.email.is_empty() { stats.name.to_lowercase() } else { stats.email.clone() };
+                let (author, active_days) = authors.entry(key).or_insert_with(|| (AuthorContribution {
+                    name: stats.name.clone(),
+                    email: stats.email.clone(),
+                    commits: 0,
+                    lines_added: 0,
+                    lines_deleted: 0,
+                    active_days: 0,
+                    last_commit_date: None,
+                    repositories: Vec::new(),
+                }, BTreeSet::new()));
+
+                // Prefer the name from the author's most recent commit anywhere
+                if stats.last_commit_date > author.last_commit_date {
+                    author.name = stats.name.clone();
+                    author.last_commit_date = stats.last_commit_date;
+                }
+                author.commits += stats.commits;
+                author.lines_added += stats.lines_added;
+                author.lines_deleted += stats.lines_deleted;
+                author.repositories.push(AuthorRepoContribution {
+                    repo_name: repo.name.clone(),
+                    repo_path: repo.path.clone(),
+                    commits: stats.commits,
+                    lines_added: stats.lines_added,
+                    lines_deleted: stats.lines_deleted,
+                    active_days: stats.active_days.len(),
+                });
+                active_days.extend(stats.active_days);
+            }
+        }
+
+        report.authors = authors.into_values()
+            .map(|(mut author, active_days)| {
+                author.active_days = active_days.len();
+                author.repositories.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.repo_name.cmp(&b.repo_name)));
+                author
+            })
+            .collect();
+        report.authors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+        report.failed_repositories.sort();
+        report.truncated_repositories.sort();
+
+        println!("REPORT: {} authors across {} repositories", report.authors.len(), report.repositories_analyzed);
+        Ok(report)
+    }
+
+    /// Commit heatmap for one repository, or summed over every cached Git repository when
+    /// `repo_path` is None. Daily counts are kept on the cache entries and reused while
+    /// HEAD is unchanged, so the workspace heatmap renders without walking history on startup.
+    pub async fn commit_heatmap(&self, cache_service: Option<&CacheService>, repo_path: Option<&str>) -> Result<CommitHeatmap, String> {
+        let today = Utc::now().date_naive();
+        let since = CommitHeatmap::grid_start(today);
+
+        let mut cache_data = match cache_service {
+            Some(cache_service) => cache_service.load_cache().await
+                .map_err(|e| format!("Failed to load cache: {}", e))?,
+            None => None,
+        };
+
+        let repo_paths: Vec<String> = match (repo_path, &cache_data) {
+            (Some(repo_path), _) => vec![repo_path.to_string()],
+            (None, Some(cache_data)) => cache_data.repositories.values()
+                .filter(|cached_repo| cached_repo.repository.is_git_repo)
+                .map(|cached_repo| cached_repo.repository.path.clone())
+                .collect(),
+            (None, None) => return Err("No cached repositories, scan a directory first".to_string()),
+        };
+
+        let mut daily_commits: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+        let mut repositories = 0;
+        let mut truncated_repositories = Vec::new();
+        let mut head_shas = HashMap::new();
+
+        for path in repo_paths {
+            let head_sha = self.git_adapter.get_head_sha(Path::new(&path));
+            let cached_heatmap = cache_data.as_ref()
+                .and_then(|cache_data| cache_data.repositories.get(&path))
+                .and_then(|cached_repo| cached_repo.heatmap.as_ref());
+
+            match (cached_heatmap, &head_sha) {
+                (Some(heatmap), Some(sha)) if heatmap.is_current(sha) => {
+                    Self::add_daily_counts(&mut daily_commits, &heatmap.daily_commits);
+                    repositories += 1;
+                    if heatmap.truncated {
+                        truncated_repositories.push(path);
+                    }
+                },
+                _ => {
+                    head_shas.insert(path, head_sha);
+                },
+            }
+        }
+
+        println!("HEATMAP: {} repositories cached, computing {}", repositories, head_shas.len());
+        let results = Self::walk_repositories(head_shas.keys().cloned().collect(), move |git_adapter, path| {
+            git_adapter.get_daily_commit_counts(path, since)
+        }).await;
+
+        let mut updated = Vec::new();
+        for (path, result) in results {
+            let (counts, truncated) = match result {
+                Ok(counts) => counts,
+                Err(e) if repo_path.is_some() => return Err(e),
+                Err(e) => {
+                    println!("HEATMAP: Failed to read history of {}: {}", path, e);
+                    continue;
+                }
+            };
+
+            Self::add_daily_counts(&mut daily_commits, &counts);
+            repositories += 1;
+
+            let cached_repo = cache_data.as_mut().and_then(|cache_data| cache_data.repositories.get_mut(&path));
+            if let (Some(cached_repo), Some(Some(sha))) = (cached_repo, head_shas.remove(&path)) {
+                cached_repo.heatmap = Some(CachedHeatmap::new(sha, since, counts, truncated));
+                updated.push(cached_repo.clone());
+            }
+            if truncated {
+                println!("HEATMAP: {} has more recent commits than are walked, counts are partial", path);
+                truncated_repositories.push(path);
+            }
+        }
+
+        if let (Some(cache_service), false) = (cache_service, updated.is_empty()) {
+            if let Err(e) = cache_service.update_entries(updated, &[]).await {
+                println!("HEATMAP: Failed to cache heatmap data: {}", e);
+            }
+        }
+
+        let mut heatmap = CommitHeatmap::from_daily_counts(&daily_commits, today, repositories);
+        truncated_repositories.sort();
+        heatmap.truncated_repositories = truncated_repositories;
+        Ok(heatmap)
+    }
+
+    fn add_daily_counts(total: &mut BTreeMap<NaiveDate, u32>, counts: &BTreeMap<NaiveDate, u32>) {
+        for (date, count) in counts {
+            *total.entry(*date).or_insert(0) += count;
+        }
+    }
+
+    /// Run a blocking history walk on each repository, at most `REPORT_WORKERS` at a time.
+    /// Results are returned in completion order.
+    async fn walk_repositories<T, F>(repo_paths: Vec<String>, walk: F) -> Vec<(String, Result<T, String>)>
+    where
+        T: Send + 'static,
+        F: Fn(&GitAdapter, &Path) -> Result<T, git2::Error> + Send + Sync + 'static,
+    {
+        let walk = Arc::new(walk);
+        let workers = Arc::new(Semaphore::new(REPORT_WORKERS));
+        let mut tasks = JoinSet::new();
+
+        for repo_path in repo_paths {
+            let walk = Arc::clone(&walk);
+            let workers = Arc::clone(&workers);
+            tasks.spawn(async move {
+                let _permit = workers.acquire_owned().await.ok();
+                let path = PathBuf::from(&repo_path);
+                let result = tokio::task::spawn_blocking(move || walk(&GitAdapter::new(), &path).map_err(|e| e.to_string()))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result);
+                (repo_path, result)
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok(result) => results.push(result),
+                Err(e) => println!("ACTIVITY: Worker failed: {}", e),
+            }
+        }
+        results
+    }
+}
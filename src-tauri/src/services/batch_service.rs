@@ -0,0 +1,334 @@
+// Batch service - runs Git operations across many repositories concurrently
+use crate::models::*;
+use crate::adapters::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+
+/// Repositories processed at once when the caller doesn't specify a limit
+pub const DEFAULT_BATCH_PARALLELISM: usize = 4;
+
+pub struct BatchService {
+    git_adapter: GitAdapter,
+}
+
+impl Default for BatchService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BatchService {
+    pub fn new() -> Self {
+        Self {
+            git_adapter: GitAdapter::new(),
+        }
+    }
+
+    /// Run the operation on every repository, at most `parallelism` at a time.
+    /// Custom commands are checked against `policy` in every repository.
+    /// `progress_callback` is called as each repository starts and finishes; results
+    /// are returned in the same order as `repo_paths`.
+    pub async fn run<F>(self: &Arc<Self>, batch_id: &str, repo_paths: Vec<PathBuf>, operation: BatchOperation, policy: GitCommandPolicy, parallelism: usize, mut progress_callback: F) -> BatchResult
+    where
+        F: FnMut(&BatchProgress),
+    {
+        let total_repos = repo_paths.len();
+        let operation = Arc::new(operation);
+        let policy = Arc::new(policy);
+        let workers = Arc::new(Semaphore::new(parallelism.max(1)));
+        let (progress_sender, mut progress_receiver) = mpsc::unbounded_channel::<BatchProgress>();
+        let mut tasks = JoinSet::new();
+        // Index and path of each worker, so a worker that panics still gets a result
+        let mut task_repos = HashMap::new();
+
+        println!("BATCH: Running {:?} on {} repositories ({} at a time)", operation.operation_type, total_repos, parallelism.max(1));
+
+        for (index, repo_path) in repo_paths.into_iter().enumerate() {
+            let service = Arc::clone(self);
+            let operation = Arc::clone(&operation);
+            let policy = Arc::clone(&policy);
+            let workers = Arc::clone(&workers);
+            let progress_sender = progress_sender.clone();
+            let batch_id = batch_id.to_string();
+            let task_repo = (index, repo_path.clone());
+
+            let task = tasks.spawn(async move {
+                let _permit = workers.acquire_owned().await.ok();
+                let repo_path_str = repo_path.to_string_lossy().to_string();
+                let progress = |state, output, error, elapsed_ms| BatchProgress {
+                    batch_id: batch_id.clone(),
+                    repo_path: repo_path_str.clone(),
+                    state,
+                    output,
+                    error,
+                    elapsed_ms,
+                    completed_count: 0,
+                    total_count: total_repos,
+                };
+
+                let _ = progress_sender.send(progress(BatchRepoState::Started, None, None, 0));
+
+                let started = Instant::now();
+                let result = service.run_single(&repo_path, &operation, &policy).await;
+                let result = Self::to_result(&repo_path, result, started.elapsed().as_millis() as u64);
+
+                let state = if result.success { BatchRepoState::Succeeded } else { BatchRepoState::Failed };
+                let _ = progress_sender.send(progress(state, Some(result.output.clone()), result.error.clone(), result.elapsed_ms));
+
+                (index, result)
+            });
+            task_repos.insert(task.id(), task_repo);
+        }
+        // Only the workers hold senders now, so the receiver closes once they're all done
+        drop(progress_sender);
+
+        let mut completed_count = 0;
+        while let Some(mut progress) = progress_receiver.recv().await {
+            if progress.state != BatchRepoState::Started {
+                completed_count += 1;
+            }
+            progress.completed_count = completed_count;
+            progress_callback(&progress);
+        }
+
+        let mut results = Vec::with_capacity(total_repos);
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok(indexed_result) => results.push(indexed_result),
+                Err(e) => {
+                    println!("BATCH: Worker failed: {}", e);
+                    let Some((index, repo_path)) = task_repos.remove(&e.id()) else {
+                        continue;
+                    };
+                    let error = match e.try_into_panic() {
+                        Ok(panic) => {
+                            let message = panic.downcast_ref::<&str>().map(|message| message.to_string())
+                                .or_else(|| panic.downcast_ref::<String>().cloned())
+                                .unwrap_or_default();
+                            format!("Operation panicked: {}", message)
+                        },
+                        Err(e) => format!("Operation did not finish: {}", e),
+                    };
+                    let result = Self::to_result(&repo_path, Err(error), 0);
+
+                    completed_count += 1;
+                    progress_callback(&BatchProgress {
+                        batch_id: batch_id.to_string(),
+                        repo_path: result.repo_path.clone(),
+                        state: BatchRepoState::Failed,
+                        output: None,
+                        error: result.error.clone(),
+                        elapsed_ms: 0,
+                        completed_count,
+                        total_count: total_repos,
+                    });
+                    results.push((index, result));
+                },
+            }
+        }
+        results.sort_by_key(|(index, _)| *index);
+
+        let results: Vec<BatchOperationResult> = results.into_iter().map(|(_, result)| result).collect();
+        let successful = results.iter().filter(|r| r.success).count();
+
+        BatchResult {
+            total_repos,
+            successful,
+            failed: total_repos - successful,
+            results,
+        }
+    }
+
+    /// Run the operation on a single repository
+    pub async fn run_single(&self, repo_path: &Path, operation: &BatchOperation, policy: &GitCommandPolicy) -> Result<String, String> {
+        if !self.git_adapter.is_git_repository(repo_path) {
+            return Err("Not a Git repository".to_string());
+        }
+
+        let result = match operation.operation_type {
+            BatchOperationType::Fetch => self.git_adapter.fetch(repo_path).await,
+            BatchOperationType::Pull => self.git_adapter.pull_fast_forward(repo_path).await,
+            BatchOperationType::Push => self.git_adapter.push(repo_path).await,
+            BatchOperationType::Status => self.git_adapter.status_summary(repo_path).await,
+            BatchOperationType::SubmoduleUpdate => self.git_adapter.submodule_update(repo_path).await,
+            BatchOperationType::Commit => {
+                let message = Self::string_parameter(operation, "message")
+                    .ok_or("Commit requires a non-empty 'message' parameter")?;
+                let stage_all = operation.parameters.get("stage_all")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                self.git_adapter.commit(repo_path, &message, stage_all).await
+            },
+            BatchOperationType::Custom => {
+                let args = Self::command_parameter(operation)?;
+                policy.check(&args, Self::confirmed_parameter(operation))?;
+
+                let timeout = Duration::from_secs(policy.timeout_secs);
+                let output = self.git_adapter.execute_command(repo_path, &args, timeout).await
+                    .map_err(|e| e.to_string())?;
+                Self::command_output(output)
+            },
+        };
+
+        result.map_err(|e| e.to_string())
+    }
+
+    fn to_result(repo_path: &Path, result: Result<String, String>, elapsed_ms: u64) -> BatchOperationResult {
+        let repo_path = repo_path.to_string_lossy().to_string();
+        match result {
+            Ok(output) => BatchOperationResult {
+                repo_path,
+                success: true,
+                output,
+                error: None,
+                elapsed_ms,
+            },
+            Err(error) => BatchOperationResult {
+                repo_path,
+                success: false,
+                output: String::new(),
+                error: Some(error),
+                elapsed_ms,
+            },
+        }
+    }
+
+    /// Whether the caller confirmed a Custom command that the policy marks as destructive
+    pub fn confirmed_parameter(operation: &BatchOperation) -> bool {
+        operation.parameters.get("confirmed")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Flatten a command result into batch output, keeping both streams on success
+    fn command_output(result: GitCommandResult) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        if result.success {
+            let combined: Vec<String> = [result.stdout, result.stderr].into_iter().filter(|s| !s.is_empty()).collect();
+            Ok(combined.join("\n"))
+        } else if result.stderr.is_empty() {
+            Err(format!("git {} exited with {:?}: {}", result.command.join(" "), result.exit_code, result.stdout).into())
+        } else {
+            Err(result.stderr.into())
+        }
+    }
+
+    fn string_parameter(operation: &BatchOperation, key: &str) -> Option<String> {
+        operation.parameters.get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// The Custom `command` parameter: git arguments as an array, or a string split with
+    /// shell quoting rules, so `commit -m "two words"` passes the message as one argument
+    pub fn command_parameter(operation: &BatchOperation) -> Result<Vec<String>, String> {
+        let args: Vec<String> = match operation.parameters.get("command") {
+            Some(serde_json::Value::Array(values)) => values.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect(),
+            Some(serde_json::Value::String(command)) => Self::split_command(command)?,
+            _ => Vec::new(),
+        };
+
+        // Accept "git status" as well as "status"
+        let args: Vec<String> = match args.first() {
+            Some(first) if first == "git" => args.into_iter().skip(1).collect(),
+            _ => args,
+        };
+
+        if args.is_empty() {
+            return Err("Custom requires a non-empty 'command' parameter".to_string());
+        }
+        Ok(args)
+    }
+
+    /// Split a command line into words like a POSIX shell: single quotes are literal, double
+    /// quotes allow `\"` and `\\`, and a backslash outside quotes escapes the next character.
+    /// Nothing is expanded.
+    fn split_command(command: &str) -> Result<Vec<String>, String> {
+        let mut words = Vec::new();
+        let mut word: Option<String> = None;
+        let mut chars = command.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                c if c.is_whitespace() => words.extend(word.take()),
+                '\'' => {
+                    let current = word.get_or_insert_with(String::new);
+                    loop {
+                        match chars.next() {
+                            Some('\'') => break,
+                            Some(c) => current.push(c),
+                            None => return Err("Unterminated single quote in command".to_string()),
+                        }
+                    }
+                },
+                '"' => {
+                    let current = word.get_or_insert_with(String::new);
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some(c @ ('"' | '\\')) => current.push(c),
+                                Some(c) => {
+                                    current.push('\\');
+                                    current.push(c);
+                                },
+                                None => return Err("Unterminated double quote in command".to_string()),
+                            },
+                            Some(c) => current.push(c),
+                            None => return Err("Unterminated double quote in command".to_string()),
+                        }
+                    }
+                },
+                '\\' => match chars.next() {
+                    Some(c) => word.get_or_insert_with(String::new).push(c),
+                    None => return Err("Command ends with a backslash".to_string()),
+                },
+                c => word.get_or_insert_with(String::new).push(c),
+            }
+        }
+
+        words.extend(word);
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(command: serde_json::Value) -> BatchOperation {
+        BatchOperation {
+            operation_type: BatchOperationType::Custom,
+            parameters: [("command".to_string(), command)].into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn split_command_follows_shell_quoting() {
+        let split = |command: &str| BatchService::split_command(command).unwrap();
+
+        assert_eq!(split("  log   --oneline "), ["log", "--oneline"]);
+        assert_eq!(split(r#"commit -m "two words""#), ["commit", "-m", "two words"]);
+        assert_eq!(split("commit -m 'it''s'"), ["commit", "-m", "its"]);
+        assert_eq!(split(r#"log --grep="fix \"quoted\" \n""#), ["log", r#"--grep=fix "quoted" \n"#]);
+        assert_eq!(split(r"add file\ name ''"), ["add", "file name", ""]);
+        assert!(BatchService::split_command("commit -m 'open").is_err());
+        assert!(BatchService::split_command(r#"commit -m "open"#).is_err());
+        assert!(BatchService::split_command("status \\").is_err());
+    }
+
+    #[test]
+    fn command_parameter_accepts_arrays_and_strings() {
+        assert_eq!(BatchService::command_parameter(&custom(serde_json::json!(["git", "commit", "-m", "a b"]))).unwrap(), ["commit", "-m", "a b"]);
+        assert_eq!(BatchService::command_parameter(&custom(serde_json::json!("git commit -m 'a b'"))).unwrap(), ["commit", "-m", "a b"]);
+        assert!(BatchService::command_parameter(&custom(serde_json::json!("git"))).is_err());
+        assert!(BatchService::command_parameter(&custom(serde_json::json!(42))).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+// Branch service - stale branch reports and cleanup across cached repositories
+use crate::models::*;
+use crate::adapters::*;
+use crate::cache::CacheService;
+use chrono::Utc;
+use std::path::Path;
+
+/// Days without commits after which a branch is reported as inactive
+pub const DEFAULT_INACTIVE_BRANCH_DAYS: u32 = 90;
+
+#[derive(Default)]
+pub struct BranchService;
+
+impl BranchService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Report stale local branches in every cached Git repository
+    pub async fn stale_branch_report(&self, cache_service: &CacheService, inactive_days: u32) -> Result<StaleBranchReport, String> {
+        let cache_data = cache_service.load_cache().await
+            .map_err(|e| format!("Failed to load cache: {}", e))?
+            .ok_or("No cached repositories, scan a directory first")?;
+
+        let mut repo_paths: Vec<String> = cache_data.repositories.into_values()
+            .filter(|cached_repo| cached_repo.repository.is_git_repo)
+            .map(|cached_repo| cached_repo.repository.path)
+            .collect();
+        repo_paths.sort();
+        println!("BRANCHES: Checking {} repositories for stale branches", repo_paths.len());
+
+        // Only refs and merge bases are inspected, so one blocking pass is enough
+        tokio::task::spawn_blocking(move || {
+            let git_adapter = GitAdapter::new();
+            let mut report = StaleBranchReport {
+                generated_at: Utc::now(),
+                inactive_days,
+                repositories_analyzed: 0,
+                failed_repositories: Vec::new(),
+                branches: Vec::new(),
+            };
+
+            for repo_path in repo_paths {
+                match git_adapter.get_stale_branches(Path::new(&repo_path), inactive_days) {
+                    Ok(branches) => {
+                        report.repositories_analyzed += 1;
+                        report.branches.extend(branches);
+                    },
+                    Err(e) => {
+                        println!("BRANCHES: Failed to read branches of {}: {}", repo_path, e);
+                        report.failed_repositories.push(repo_path);
+                    }
+                }
+            }
+
+            println!("BRANCHES: Found {} stale branches", report.branches.len());
+            report
+        })
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Delete the selected branches, recording each tip before its branch is deleted so
+    /// it can be restored. A dry run performs the same checks without deleting or recording anything.
+    pub async fn delete_branches(&self, cache_service: &CacheService, requests: Vec<BranchDeletionRequest>, dry_run: bool) -> Result<BranchCleanupResult, String> {
+        let checked = tokio::task::spawn_blocking(move || {
+            let git_adapter = GitAdapter::new();
+            requests.into_iter()
+                .map(|request| {
+                    let outcome = git_adapter.delete_branch(Path::new(&request.repo_path), &request.branch, &request.tip_sha, true)
+                        .map_err(|e| e.to_string());
+                    (request, outcome)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let records: Vec<DeletedBranchRecord> = checked.iter()
+            .filter_map(|(_, outcome)| outcome.as_ref().ok().cloned())
+            .collect();
+        if !dry_run && !records.is_empty() {
+            cache_service.record_deleted_branches(records).await
+                .map_err(|e| format!("No branches were deleted, recording their tips failed: {}", e))?;
+        }
+
+        let (results, failed_records) = tokio::task::spawn_blocking(move || {
+            let git_adapter = GitAdapter::new();
+            let mut results = Vec::new();
+            let mut failed_records = Vec::new();
+
+            for (request, outcome) in checked {
+                let outcome = outcome.and_then(|record| {
+                    if dry_run {
+                        return Ok(record);
+                    }
+                    match git_adapter.delete_branch(Path::new(&request.repo_path), &request.branch, &request.tip_sha, false) {
+                        Ok(_) => {
+                            println!("BRANCHES: Deleted {} in {} (was {})", record.branch, record.repo_path, record.tip_sha);
+                            Ok(record)
+                        },
+                        Err(e) => {
+                            failed_records.push(record);
+                            Err(e.to_string())
+                        },
+                    }
+                });
+
+                results.push(BranchDeletionResult {
+                    repo_path: request.repo_path,
+                    branch: request.branch,
+                    success: outcome.is_ok(),
+                    tip_sha: outcome.as_ref().ok().map(|record| record.tip_sha.clone()),
+                    error: outcome.err(),
+                });
+            }
+
+            (results, failed_records)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+        // Branches that are still there don't need restoring
+        if !failed_records.is_empty() {
+            let was_kept = |kept: &DeletedBranchRecord| failed_records.iter().any(|record| {
+                record.repo_path == kept.repo_path && record.branch == kept.branch
+                    && record.tip_sha == kept.tip_sha && record.deleted_at == kept.deleted_at
+            });
+            if let Err(e) = cache_service.forget_deleted_branches(was_kept).await {
+                println!("BRANCHES: Failed to drop records of branches that weren't deleted: {}", e);
+            }
+        }
+
+        let deleted_count = results.iter().filter(|result| result.success).count();
+        Ok(BranchCleanupResult {
+            dry_run,
+            deleted: deleted_count,
+            failed: results.len() - deleted_count,
+            results,
+        })
+    }
+
+    /// Recreate a deleted branch at its recorded tip and drop its record, so it isn't
+    /// offered for restoring again
+    pub async fn restore_branch(&self, cache_service: &CacheService, repo_path: String, branch: String, tip_sha: String) -> Result<(), String> {
+        GitAdapter::new().restore_branch(Path::new(&repo_path), &branch, &tip_sha)
+            .map_err(|e| format!("Failed to restore branch: {}", e))?;
+        println!("BRANCHES: Restored {} in {} at {}", branch, repo_path, tip_sha);
+
+        let was_restored = |record: &DeletedBranchRecord| {
+            record.repo_path == repo_path && record.branch == branch && record.tip_sha == tip_sha
+        };
+        if let Err(e) = cache_service.forget_deleted_branches(was_restored).await {
+            println!("BRANCHES: Failed to drop the record of restored branch {}: {}", branch, e);
+        }
+        Ok(())
+    }
+}
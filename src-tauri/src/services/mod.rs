@@ -1,4 +1,16 @@
 // Services - business logic layer
 pub mod repository_service;
+pub mod scan_handle;
+pub mod watcher_service;
+pub mod batch_service;
+pub mod activity_service;
+pub mod branch_service;
+pub mod stash_service;
 
-pub use repository_service::*;
\ No newline at end of file
+pub use repository_service::*;
+pub use scan_handle::*;
+pub use watcher_service::*;
+pub use batch_service::*;
+pub use activity_service::*;
+pub use branch_service::*;
+pub use stash_service::*;
\ No newline at end of file
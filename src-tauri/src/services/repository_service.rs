@@ -1,13 +1,29 @@
 // Repository service - orchestrates adapters to scan and analyze repositories
 use crate::models::*;
 use crate::adapters::*;
-use std::path::Path;
+use crate::cache::RepoFingerprint;
+use super::ScanHandle;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::runtime::Handle;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Upper bound on directories analyzed concurrently (tokei and git2 are I/O heavy)
+const MAX_ANALYSIS_WORKERS: usize = 8;
 
 pub struct RepositoryService {
     git_adapter: GitAdapter,
     tokei_adapter: TokeiAdapter,
     filesystem_adapter: FilesystemAdapter,
-    ignore_adapter: IgnoreAdapter,
+    workspace_adapter: WorkspaceAdapter,
+}
+
+impl Default for RepositoryService {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RepositoryService {
@@ -16,39 +32,55 @@ impl RepositoryService {
             git_adapter: GitAdapter::new(),
             tokei_adapter: TokeiAdapter::new(),
             filesystem_adapter: FilesystemAdapter::new(),
-            ignore_adapter: IgnoreAdapter::new(),
+            workspace_adapter: WorkspaceAdapter::new(),
         }
     }
 
-    pub async fn scan_directory(&self, base_path: &Path) -> Result<Vec<Repository>, Box<dyn std::error::Error + Send + Sync>> {
-        self.scan_directory_with_progress(base_path, |_, _, _| {}).await
+    pub async fn scan_directory(self: &Arc<Self>, base_path: &Path, rules: &ScanRules) -> Result<Vec<Repository>, Box<dyn std::error::Error + Send + Sync>> {
+        self.scan_directory_with_progress(base_path, rules, |_, _, _| {}).await
     }
 
-    pub async fn scan_directory_with_progress<F>(&self, base_path: &Path, mut progress_callback: F) -> Result<Vec<Repository>, Box<dyn std::error::Error + Send + Sync>>
+    pub async fn scan_directory_with_progress<F>(self: &Arc<Self>, base_path: &Path, rules: &ScanRules, progress_callback: F) -> Result<Vec<Repository>, Box<dyn std::error::Error + Send + Sync>>
     where
         F: FnMut(&str, usize, usize),  // Changed to include total count
     {
-        let mut repositories = Vec::new();
+        let scan = ScanHandle::new();
+        let dirs_to_scan = {
+            let service = Arc::clone(self);
+            let base_path = base_path.to_path_buf();
+            let rules = rules.clone();
+            let scan = scan.clone();
+            tokio::task::spawn_blocking(move || service.discover_directories(&base_path, &rules, &scan)).await?
+        };
+        let repositories = self.analyze_directories_with_progress(dirs_to_scan, &HashMap::new(), rules, &scan, progress_callback).await;
         
+        Ok(repositories)
+    }
+
+    /// First pass: walk base_path and collect the directories that `rules` let through.
+    /// Stops early (returning what was found so far) if the scan is cancelled.
+    pub fn discover_directories(&self, base_path: &Path, rules: &ScanRules, scan: &ScanHandle) -> Vec<PathBuf> {
         // Special case: if the base path itself is a Git repository, only analyze that
         println!("Checking if base path is Git repository: {}", base_path.display());
         if self.git_adapter.is_git_repository(base_path) {
             println!("Base path IS a Git repository, analyzing single directory");
-            progress_callback(&base_path.display().to_string(), 1, 1);
-            println!("About to analyze directory: {}", base_path.display());
-            let repo = self.analyze_directory(base_path).await;
-            println!("Directory analysis completed");
-            repositories.push(repo);
-            return Ok(repositories);
+            return vec![base_path.to_path_buf()];
         }
         println!("Base path is NOT a Git repository, scanning subdirectories");
         
-        // First pass: count total directories to scan
         println!("Counting directories to scan...");
-        let walker = self.ignore_adapter.create_walker(base_path, Some(3));
+        let depth = rules.depth_for(base_path);
+        println!("Scan depth: {:?}", depth);
+        let ignore_adapter = IgnoreAdapter::with_rules(rules);
+        let walker = ignore_adapter.create_walker(base_path, depth.max_depth());
         let mut dirs_to_scan = Vec::new();
         
         for result in walker {
+            if scan.is_cancelled() {
+                println!("Scan cancelled while counting directories");
+                break;
+            }
+            
             if let Ok(entry) = result {
                 let path = entry.path();
                 
@@ -57,13 +89,13 @@ impl RepositoryService {
                     continue;
                 }
                 
-                // Skip if this is a subdirectory of a Git repo we're processing
-                if path != base_path && self.is_inside_git_repo(path, base_path) {
+                // Apply directory filtering
+                if ignore_adapter.should_skip_directory(path) {
                     continue;
                 }
                 
-                // Apply directory filtering
-                if self.ignore_adapter.should_skip_directory(path) {
+                // A Git directory whose working tree lives elsewhere is reported at the working tree
+                if self.git_adapter.is_detached_git_dir(path) {
                     continue;
                 }
                 
@@ -71,34 +103,120 @@ impl RepositoryService {
             }
         }
         
+        println!("Found {} directories to scan", dirs_to_scan.len());
+        dirs_to_scan
+    }
+
+    /// Capture the change-detection markers used to decide whether a cached entry is stale
+    pub fn collect_fingerprint(&self, dir_path: &Path) -> RepoFingerprint {
+        if self.git_adapter.is_git_repository(dir_path) {
+            RepoFingerprint {
+                git_head_sha: self.git_adapter.get_head_sha(dir_path),
+                last_modified: self.git_adapter.get_index_modified(dir_path),
+                worktree_signature: self.git_adapter.get_worktree_signature(dir_path),
+            }
+        } else {
+            RepoFingerprint {
+                git_head_sha: None,
+                last_modified: self.filesystem_adapter.get_last_activity(dir_path).unwrap_or(None),
+                worktree_signature: None,
+            }
+        }
+    }
+
+    /// Collect fingerprints for every directory, keyed by the same path string used in `Repository::path`.
+    /// Stops early (returning what was collected so far) if the scan is cancelled.
+    pub fn collect_fingerprints(&self, dirs: &[PathBuf], scan: &ScanHandle) -> HashMap<String, RepoFingerprint> {
+        dirs.iter()
+            .take_while(|_| !scan.is_cancelled())
+            .map(|dir| (dir.to_string_lossy().to_string(), self.collect_fingerprint(dir)))
+            .collect()
+    }
+
+    /// Second pass: analyze directories with progress, reusing `fresh` results for directories
+    /// that haven't changed since they were cached. Reused Git repos only get their cheap
+    /// status fields refreshed; tokei and size calculation are skipped.
+    ///
+    /// Directories are analyzed on a bounded pool of blocking workers. Progress is reported
+    /// as each directory completes, so counts are monotonic even though completion order isn't.
+    /// If the scan is cancelled, directories not yet analyzed are skipped and the partial
+    /// results are returned.
+    pub async fn analyze_directories_with_progress<F>(self: &Arc<Self>, dirs_to_scan: Vec<PathBuf>, fresh: &HashMap<String, Repository>, rules: &ScanRules, scan: &ScanHandle, mut progress_callback: F) -> Vec<Repository>
+    where
+        F: FnMut(&str, usize, usize),
+    {
+        let mut repositories = Vec::new();
         let total_count = dirs_to_scan.len();
-        println!("Found {} directories to scan", total_count);
-        
-        // Second pass: actually scan directories with progress
         let mut scanned_count = 0;
         
+        let workers = Arc::new(Semaphore::new(Self::analysis_parallelism()));
+        let rules = Arc::new(rules.clone());
+        let mut tasks = JoinSet::new();
+        
         for dir_path in dirs_to_scan {
+            let service = Arc::clone(self);
+            let workers = Arc::clone(&workers);
+            let rules = Arc::clone(&rules);
+            let scan = scan.clone();
+            let cached_repo = fresh.get(dir_path.to_string_lossy().as_ref()).cloned();
+            
+            tasks.spawn(async move {
+                let _permit = workers.acquire_owned().await.ok();
+                tokio::task::spawn_blocking(move || {
+                    let repo = Handle::current().block_on(async {
+                        match cached_repo {
+                            Some(mut repo) if !scan.is_cancelled() => {
+                                service.refresh_git_state(&mut repo).await;
+                                service.refresh_sub_projects(&mut repo, &rules);
+                                Some(repo)
+                            },
+                            Some(_) => None,
+                            None => service.analyze_directory(&dir_path, &rules, &scan).await,
+                        }
+                    })?;
+                    
+                    // Only include directories that look like projects (after analyzing)
+                    let is_project = service.is_project(&repo, &dir_path);
+                    Some((dir_path, is_project.then_some(repo)))
+                }).await
+            });
+        }
+        
+        while let Some(result) = tasks.join_next().await {
+            let (dir_path, repo) = match result {
+                Ok(Ok(Some(analyzed))) => analyzed,
+                // Skipped because the scan was cancelled
+                Ok(Ok(None)) => continue,
+                Ok(Err(e)) | Err(e) => {
+                    println!("ANALYZE: Worker failed: {}", e);
+                    continue;
+                }
+            };
+            
             scanned_count += 1;
             progress_callback(&dir_path.display().to_string(), scanned_count, total_count);
             
-            let repo = self.analyze_directory(&dir_path).await;
-            
-            // Only include directories that look like projects (after analyzing)
-            if !repo.is_git_repo && repo.code_lines < 10 && !self.filesystem_adapter.has_project_indicators(&dir_path) {
-                continue;
+            if let Some(repo) = repo {
+                repositories.push(repo);
             }
-            
-            repositories.push(repo);
         }
 
         // Sort by name for consistent ordering
         repositories.sort_by(|a, b| a.name.cmp(&b.name));
         
-        Ok(repositories)
+        repositories
+    }
+
+    /// Number of directories analyzed concurrently
+    fn analysis_parallelism() -> usize {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+            .min(MAX_ANALYSIS_WORKERS)
     }
 
-    pub async fn get_directory_stats(&self, base_path: &Path) -> Result<DirectoryStats, Box<dyn std::error::Error + Send + Sync>> {
-        let repos = self.scan_directory(base_path).await.map_err(|e| -> Box<dyn std::error::Error + Send + Sync> { e })?;
+    pub async fn get_directory_stats(self: &Arc<Self>, base_path: &Path, rules: &ScanRules) -> Result<DirectoryStats, Box<dyn std::error::Error + Send + Sync>> {
+        let repos = self.scan_directory(base_path, rules).await.map_err(|e| -> Box<dyn std::error::Error + Send + Sync> { e })?;
         
         let total_directories = repos.len() as u32;
         let git_repositories = repos.iter().filter(|r| r.is_git_repo).count() as u32;
@@ -117,19 +235,30 @@ impl RepositoryService {
         largest_repos.sort_by(|a, b| b.size_mb.partial_cmp(&a.size_mb).unwrap_or(std::cmp::Ordering::Equal));
         largest_repos.truncate(10);
         
-        // Get most active repos (by last activity)
+        // Get most active repos (by last commit, falling back to filesystem activity for non-Git dirs)
         let mut most_active_repos = repos.clone();
         most_active_repos.sort_by(|a, b| {
-            b.last_activity.unwrap_or_default()
-                .cmp(&a.last_activity.unwrap_or_default())
+            b.last_commit_date.or(b.last_activity).unwrap_or_default()
+                .cmp(&a.last_commit_date.or(a.last_activity).unwrap_or_default())
         });
         most_active_repos.truncate(10);
         
-        // Repos needing attention (have uncommitted changes or no remotes)
-        let repos_needing_attention = repos.into_iter()
-            .filter(|r| r.is_git_repo && (r.has_uncommitted_changes || r.remotes.is_empty()))
-            .take(20)
+        // Feature branches that have fallen behind the default branch (top 10)
+        let mut repos_behind_default: Vec<Repository> = repos.iter()
+            .filter(|r| r.is_git_repo && !r.is_default_branch && r.behind_default > 0)
+            .cloned()
             .collect();
+        repos_behind_default.sort_by_key(|repo| std::cmp::Reverse(repo.behind_default));
+        repos_behind_default.truncate(10);
+        
+        // Repos needing attention (have uncommitted changes, unpushed commits or no remotes).
+        // Repos stuck mid-merge/rebase or with conflicts come first.
+        let mut repos_needing_attention: Vec<Repository> = repos.into_iter()
+            .filter(|r| r.is_git_repo && (r.has_uncommitted_changes || r.ahead > 0 || r.remotes.is_empty()
+                || r.operation_in_progress.is_some() || !r.conflicted_files.is_empty()))
+            .collect();
+        repos_needing_attention.sort_by_key(|r| r.operation_in_progress.is_none() && r.conflicted_files.is_empty());
+        repos_needing_attention.truncate(20);
 
         Ok(DirectoryStats {
             total_directories,
@@ -141,10 +270,21 @@ impl RepositoryService {
             largest_repos,
             most_active_repos,
             repos_needing_attention,
+            repos_behind_default,
         })
     }
 
-    async fn analyze_directory(&self, dir_path: &Path) -> Repository {
+    /// Check whether an analyzed directory should be reported as a project
+    pub fn is_project(&self, repo: &Repository, dir_path: &Path) -> bool {
+        repo.is_git_repo || repo.code_lines >= 10 || self.filesystem_adapter.has_project_indicators(dir_path)
+    }
+
+    /// Analyze a single directory. Returns None if the scan was cancelled part-way through.
+    pub async fn analyze_directory(&self, dir_path: &Path, rules: &ScanRules, scan: &ScanHandle) -> Option<Repository> {
+        if scan.is_cancelled() {
+            return None;
+        }
+        
         println!("ANALYZE: Starting analysis of {}", dir_path.display());
         
         let name = dir_path.file_name()
@@ -157,22 +297,34 @@ impl RepositoryService {
         println!("ANALYZE: Calculating directory size...");
         let size_mb = self.filesystem_adapter.calculate_directory_size(dir_path).unwrap_or(0.0);
         println!("ANALYZE: Size calculated: {} MB", size_mb);
+        
+        if scan.is_cancelled() {
+            return None;
+        }
+
+        println!("ANALYZE: Checking if Git repository...");
+        let repo_kind = self.git_adapter.get_repo_kind(dir_path);
 
         // Skip tokei for large directories or non-git directories with many subdirs
-        let (primary_language, total_lines, code_lines) = if size_mb > 10.0 && !self.git_adapter.is_git_repository(dir_path) {
+        let (primary_language, total_lines, code_lines) = if size_mb > 10.0 && repo_kind.is_none() {
             println!("ANALYZE: Skipping tokei for large non-git directory");
             (Some("Mixed".to_string()), 0, 0)
+        } else if repo_kind == Some(RepoKind::Bare) {
+            println!("ANALYZE: Skipping tokei for bare repository");
+            (None, 0, 0)
         } else {
             println!("ANALYZE: Starting tokei language analysis...");
             let result = self.tokei_adapter.analyze_languages(dir_path);
             println!("ANALYZE: Tokei completed. Primary language: {:?}, Lines: {}", result.0, result.1);
             result
         };
+        
+        if scan.is_cancelled() {
+            return None;
+        }
 
-        // Check if it's a git repository using git adapter
-        println!("ANALYZE: Checking if Git repository...");
-        if self.git_adapter.is_git_repository(dir_path) {
-            println!("ANALYZE: IS Git repository, getting Git info...");
+        if let Some(repo_kind) = repo_kind {
+            println!("ANALYZE: IS Git repository ({:?}), getting Git info...", repo_kind);
             // Get Git status and information
             let git_status = self.git_adapter.get_status(dir_path).await.ok();
             let remotes = self.git_adapter.get_remotes(dir_path).await
@@ -188,40 +340,66 @@ impl RepositoryService {
             let current_branch = git_status.as_ref()
                 .and_then(|s| s.current_branch.clone());
 
+            let (ahead, behind) = git_status.as_ref()
+                .map(|s| (s.ahead, s.behind))
+                .unwrap_or((0, 0));
+
+            let default_branch = self.git_adapter.get_default_branch_status(dir_path);
+
             let last_activity = self.filesystem_adapter.get_last_activity(dir_path).unwrap_or(None);
+            let last_commit_date = self.git_adapter.get_head_commit_time(dir_path);
+            let (commit_count, commit_count_truncated) = match self.git_adapter.count_commits(dir_path) {
+                Some((count, truncated)) => (Some(count), truncated),
+                None => (None, false),
+            };
 
-            // Determine status from git status
-            let status = if has_uncommitted_changes {
-                if git_status.as_ref().map(|s| !s.unstaged_files.is_empty() || !s.staged_files.is_empty()).unwrap_or(false) {
-                    RepoStatus::Dirty
-                } else {
-                    RepoStatus::Untracked
-                }
+            let status = Self::status_from_git(git_status.as_ref());
+            let operation_in_progress = git_status.as_ref().and_then(|s| s.operation_in_progress);
+            let conflicted_files = git_status.as_ref().map(|s| s.conflicted_files.clone()).unwrap_or_default();
+            let stash_count = self.git_adapter.get_stash_count(dir_path);
+            let submodules = self.git_adapter.list_submodules(dir_path).unwrap_or_default();
+            let worktrees = self.owned_worktrees(dir_path, &repo_kind);
+            let sub_projects = if rules.sub_projects {
+                self.find_sub_projects(dir_path, rules)
             } else {
-                RepoStatus::Clean
+                vec![]
             };
 
-            Repository {
+            Some(Repository {
                 name,
                 path,
                 is_git_repo: true,
                 has_uncommitted_changes,
                 current_branch,
                 remotes,
-                last_commit_date: None, // TODO: implement in git_adapter
+                last_commit_date,
                 last_activity,
                 status,
                 size_mb,
-                commit_count: None, // TODO: implement in git_adapter  
+                commit_count,
+                commit_count_truncated,
                 primary_language,
                 total_lines,
                 code_lines,
-            }
+                ahead,
+                behind,
+                default_branch: default_branch.as_ref().map(|d| d.name.clone()),
+                is_default_branch: default_branch.as_ref().is_some_and(|d| d.is_current),
+                ahead_of_default: default_branch.as_ref().map(|d| d.ahead).unwrap_or(0),
+                behind_default: default_branch.as_ref().map(|d| d.behind).unwrap_or(0),
+                operation_in_progress,
+                conflicted_files,
+                stash_count,
+                submodules,
+                repo_kind: Some(repo_kind),
+                worktrees,
+                sub_projects,
+            })
         } else {
             // Not a git repository
             let last_activity = self.filesystem_adapter.get_last_activity(dir_path).unwrap_or(None);
 
-            Repository {
+            Some(Repository {
                 name,
                 path,
                 is_git_repo: false,
@@ -233,31 +411,130 @@ impl RepositoryService {
                 status: RepoStatus::NoGit,
                 size_mb,
                 commit_count: None,
+                commit_count_truncated: false,
                 primary_language,
                 total_lines,
                 code_lines,
-            }
+                ahead: 0,
+                behind: 0,
+                default_branch: None,
+                is_default_branch: false,
+                ahead_of_default: 0,
+                behind_default: 0,
+                operation_in_progress: None,
+                conflicted_files: vec![],
+                stash_count: 0,
+                submodules: vec![],
+                repo_kind: None,
+                worktrees: vec![],
+                sub_projects: vec![],
+            })
         }
     }
 
-    fn is_inside_git_repo(&self, path: &Path, base_path: &Path) -> bool {
-        let mut current = path.parent();
+    /// Refresh the Git-derived fields of a previously analyzed repository
+    pub async fn refresh_git_state(&self, repo: &mut Repository) {
+        if !repo.is_git_repo {
+            return;
+        }
+
+        let git_status = self.git_adapter.get_status(Path::new(&repo.path)).await.ok();
         
-        while let Some(parent) = current {
-            // Stop if we've reached the base path
-            if parent == base_path {
-                break;
-            }
-            
-            // Check if this parent is a Git repository
-            if self.git_adapter.is_git_repository(parent) {
-                return true;
+        repo.has_uncommitted_changes = git_status.as_ref().map(|s| !s.is_clean).unwrap_or(false);
+        repo.current_branch = git_status.as_ref().and_then(|s| s.current_branch.clone());
+        repo.ahead = git_status.as_ref().map(|s| s.ahead).unwrap_or(0);
+        repo.behind = git_status.as_ref().map(|s| s.behind).unwrap_or(0);
+        repo.status = Self::status_from_git(git_status.as_ref());
+        repo.operation_in_progress = git_status.as_ref().and_then(|s| s.operation_in_progress);
+        repo.conflicted_files = git_status.as_ref().map(|s| s.conflicted_files.clone()).unwrap_or_default();
+        repo.stash_count = self.git_adapter.get_stash_count(Path::new(&repo.path));
+        repo.submodules = self.git_adapter.list_submodules(Path::new(&repo.path)).unwrap_or_default();
+        repo.repo_kind = self.git_adapter.get_repo_kind(Path::new(&repo.path));
+        repo.worktrees = match &repo.repo_kind {
+            Some(repo_kind) => self.owned_worktrees(Path::new(&repo.path), repo_kind),
+            None => vec![],
+        };
+
+        // origin's default branch can move on fetch without HEAD changing
+        let default_branch = self.git_adapter.get_default_branch_status(Path::new(&repo.path));
+        repo.default_branch = default_branch.as_ref().map(|d| d.name.clone());
+        repo.is_default_branch = default_branch.as_ref().is_some_and(|d| d.is_current);
+        repo.ahead_of_default = default_branch.as_ref().map(|d| d.ahead).unwrap_or(0);
+        repo.behind_default = default_branch.as_ref().map(|d| d.behind).unwrap_or(0);
+    }
+
+    /// Workspace members declared by the manifests at the root of the repository at
+    /// `repo_path`, each with its own language and line stats
+    pub fn find_sub_projects(&self, repo_path: &Path, rules: &ScanRules) -> Vec<SubProject> {
+        let workspaces = self.workspace_adapter.find_workspaces(repo_path);
+        if workspaces.is_empty() {
+            return vec![];
+        }
+        
+        let ignore_adapter = IgnoreAdapter::with_rules(rules);
+        let mut seen = HashSet::new();
+        let mut sub_projects = Vec::new();
+        
+        for workspace in workspaces {
+            for member_path in ignore_adapter.find_directories(repo_path, &workspace.members, &workspace.exclude) {
+                // A directory declared by several manifests (package.json and pnpm) is reported once
+                if !member_path.join(workspace.kind.member_manifest()).is_file() || !seen.insert(member_path.clone()) {
+                    continue;
+                }
+                
+                println!("ANALYZE: Sub-project {} ({:?} workspace)", member_path.display(), workspace.kind);
+                let (primary_language, total_lines, code_lines) = self.tokei_adapter.count_lines(&member_path);
+                let name = self.workspace_adapter.member_name(workspace.kind, &member_path)
+                    .or_else(|| member_path.file_name().map(|name| name.to_string_lossy().to_string()))
+                    .unwrap_or_default();
+                
+                sub_projects.push(SubProject {
+                    name,
+                    path: member_path.to_string_lossy().to_string(),
+                    relative_path: member_path.strip_prefix(repo_path).unwrap_or(&member_path).to_string_lossy().to_string(),
+                    workspace: workspace.kind,
+                    primary_language,
+                    total_lines,
+                    code_lines,
+                });
             }
-            
-            current = parent.parent();
         }
         
-        false
+        sub_projects.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+        sub_projects
+    }
+
+    /// Bring a cached entry's sub-projects in line with the current rules. Members already
+    /// found aren't recounted; they're only looked up if discovery was off when it was cached.
+    fn refresh_sub_projects(&self, repo: &mut Repository, rules: &ScanRules) {
+        if !rules.sub_projects {
+            repo.sub_projects.clear();
+        } else if repo.is_git_repo && repo.sub_projects.is_empty() {
+            repo.sub_projects = self.find_sub_projects(Path::new(&repo.path), rules);
+        }
+    }
+
+    /// Linked worktrees grouped under a repository. A worktree itself lists none, since
+    /// git reports the main repository's worktrees from any of them.
+    fn owned_worktrees(&self, dir_path: &Path, repo_kind: &RepoKind) -> Vec<WorktreeInfo> {
+        match repo_kind {
+            RepoKind::Worktree { .. } => vec![],
+            _ => self.git_adapter.list_worktrees(dir_path).unwrap_or_default(),
+        }
+    }
+
+    /// Determine the repository status from its Git status
+    fn status_from_git(git_status: Option<&GitStatus>) -> RepoStatus {
+        match git_status {
+            Some(s) if !s.is_clean => {
+                if !s.unstaged_files.is_empty() || !s.staged_files.is_empty() || !s.conflicted_files.is_empty() {
+                    RepoStatus::Dirty
+                } else {
+                    RepoStatus::Untracked
+                }
+            },
+            _ => RepoStatus::Clean,
+        }
     }
 
 }
\ No newline at end of file
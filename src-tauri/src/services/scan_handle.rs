@@ -0,0 +1,25 @@
+// Scan handles - cooperative cancellation for running scans
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared cancellation flag checked by the walker and analysis workers.
+/// Clones observe the same flag, so the command layer can keep one to cancel a scan.
+#[derive(Debug, Clone, Default)]
+pub struct ScanHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ScanHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation; running work stops at its next checkpoint
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
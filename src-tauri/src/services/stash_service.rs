@@ -0,0 +1,64 @@
+// Stash service - workspace-wide stash inventory
+use crate::models::*;
+use crate::adapters::*;
+use crate::cache::CacheService;
+use chrono::{Duration, Utc};
+use std::path::Path;
+
+/// Age after which a stash is reported as forgotten
+pub const DEFAULT_STASH_AGE_DAYS: u32 = 30;
+
+#[derive(Default)]
+pub struct StashService;
+
+impl StashService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Report stashes older than `older_than_days` in every cached Git repository
+    pub async fn old_stash_report(&self, cache_service: &CacheService, older_than_days: u32) -> Result<StashReport, String> {
+        let cache_data = cache_service.load_cache().await
+            .map_err(|e| format!("Failed to load cache: {}", e))?
+            .ok_or("No cached repositories, scan a directory first")?;
+
+        let mut repo_paths: Vec<String> = cache_data.repositories.into_values()
+            .filter(|cached_repo| cached_repo.repository.is_git_repo)
+            .map(|cached_repo| cached_repo.repository.path)
+            .collect();
+        repo_paths.sort();
+        println!("STASHES: Checking {} repositories for stashes older than {} days", repo_paths.len(), older_than_days);
+
+        let cutoff = Utc::now() - Duration::days(older_than_days as i64);
+
+        tokio::task::spawn_blocking(move || {
+            let git_adapter = GitAdapter::new();
+            let mut report = StashReport {
+                generated_at: Utc::now(),
+                older_than_days,
+                repositories_analyzed: 0,
+                failed_repositories: Vec::new(),
+                stashes: Vec::new(),
+            };
+
+            for repo_path in repo_paths {
+                match git_adapter.list_stashes(Path::new(&repo_path)) {
+                    Ok(stashes) => {
+                        report.repositories_analyzed += 1;
+                        report.stashes.extend(stashes.into_iter().filter(|stash| stash.date.is_some_and(|date| date < cutoff)));
+                    },
+                    Err(e) => {
+                        println!("STASHES: Failed to read stashes of {}: {}", repo_path, e);
+                        report.failed_repositories.push(repo_path);
+                    }
+                }
+            }
+
+            report.stashes.sort_by_key(|stash| stash.date);
+            println!("STASHES: Found {} old stashes", report.stashes.len());
+            report
+        })
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
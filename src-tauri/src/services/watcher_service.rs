@@ -0,0 +1,328 @@
+// Watcher service - keeps the repository cache live by reacting to filesystem changes
+use crate::models::ScanRules;
+use crate::adapters::*;
+use crate::cache::{CacheService, CachedRepository};
+use crate::services::{RepositoryService, ScanHandle};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+
+/// Upper bound on watched directories so huge trees never exhaust OS watch limits
+pub const MAX_WATCHED_DIRECTORIES: usize = 2000;
+
+/// How long changes must settle before the affected repositories are refreshed
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(750);
+
+/// Files inside the Git directory whose change means the repository state changed. A commit or
+/// `update-ref` on the checked-out branch moves `refs/heads/<branch>`, which only shows up
+/// in the watched directories as a new `logs/HEAD` entry.
+const GIT_TRIGGER_FILES: &[&str] = &["HEAD", "index", "FETCH_HEAD", "ORIG_HEAD", "logs/HEAD"];
+
+/// Trigger files that change without the checkout changing, so only the Git state is refreshed
+const GIT_STATE_FILES: &[&str] = &["index", "FETCH_HEAD"];
+
+/// Directories for a watcher to register, built by `RepositoryWatcher::watch_set`
+pub struct WatchSet {
+    /// Repository roots with their Git directories
+    repositories: Vec<(PathBuf, PathBuf)>,
+    /// Candidate directories under the scan roots
+    directories: Vec<PathBuf>,
+}
+
+/// Watch registrations shared between the watcher and its event-processing task
+struct WatchState {
+    debouncer: RepoDebouncer,
+    watched: HashSet<PathBuf>,
+    /// Repository root for each watched Git directory
+    git_dirs: HashMap<PathBuf, PathBuf>,
+}
+
+impl WatchState {
+    /// Register a directory, respecting the watch cap. Returns false if it wasn't watched.
+    fn watch(&mut self, watch_adapter: &WatchAdapter, dir_path: &Path) -> bool {
+        if self.watched.contains(dir_path) {
+            return true;
+        }
+        if self.watched.len() >= MAX_WATCHED_DIRECTORIES {
+            return false;
+        }
+
+        match watch_adapter.watch_directory(&mut self.debouncer, dir_path) {
+            Ok(_) => {
+                self.watched.insert(dir_path.to_path_buf());
+                true
+            },
+            Err(e) => {
+                println!("WATCH: Failed to watch {}: {}", dir_path.display(), e);
+                false
+            }
+        }
+    }
+
+    fn unwatch(&mut self, watch_adapter: &WatchAdapter, dir_path: &Path) {
+        if self.watched.remove(dir_path) {
+            let _ = watch_adapter.unwatch_directory(&mut self.debouncer, dir_path);
+        }
+    }
+
+    /// Watch a repository's Git directory and its `logs`. Returns false if it wasn't watched.
+    fn watch_repository(&mut self, watch_adapter: &WatchAdapter, repo_root: &Path, git_dir: &Path) -> bool {
+        if !self.watch(watch_adapter, git_dir) {
+            return false;
+        }
+
+        self.git_dirs.insert(git_dir.to_path_buf(), repo_root.to_path_buf());
+        let logs_dir = git_dir.join("logs");
+        if logs_dir.is_dir() {
+            self.watch(watch_adapter, &logs_dir);
+        }
+        true
+    }
+
+    /// Stop watching a repository's directory and Git directory
+    fn unwatch_repository(&mut self, watch_adapter: &WatchAdapter, repo_root: &Path) {
+        let git_dirs: Vec<PathBuf> = self.git_dirs.iter()
+            .filter(|(_, root)| root.as_path() == repo_root)
+            .map(|(git_dir, _)| git_dir.clone())
+            .collect();
+
+        for git_dir in git_dirs {
+            self.git_dirs.remove(&git_dir);
+            self.unwatch(watch_adapter, &git_dir.join("logs"));
+            self.unwatch(watch_adapter, &git_dir);
+        }
+        self.unwatch(watch_adapter, repo_root);
+    }
+}
+
+/// Background watcher over the scanned paths. Dropping it stops watching.
+pub struct RepositoryWatcher {
+    state: Arc<Mutex<WatchState>>,
+}
+
+impl RepositoryWatcher {
+    /// Directories to watch: the Git directory of every known repository (for HEAD and
+    /// index changes), then the candidate directories under each scan root (for new or
+    /// removed repositories), found with `rules` like a scan would. This opens every
+    /// repository and walks the scan roots, so call it from a blocking thread.
+    pub fn watch_set(scanned_paths: &[PathBuf], repo_paths: &[String], rules: &ScanRules) -> WatchSet {
+        let service = RepositoryService::new();
+        let git_adapter = GitAdapter::new();
+
+        let repositories = repo_paths.iter()
+            .filter_map(|repo_path| {
+                let git_dir = git_adapter.git_dir(Path::new(repo_path))?;
+                Some((PathBuf::from(repo_path), git_dir))
+            })
+            .collect();
+        let directories = scanned_paths.iter()
+            .flat_map(|root| service.discover_directories(root, rules, &ScanHandle::new()))
+            .collect();
+
+        WatchSet { repositories, directories }
+    }
+
+    /// Start watching `watch_set` up to `MAX_WATCHED_DIRECTORIES`. New directories are
+    /// filtered with `rules`.
+    pub fn start(app: AppHandle, watch_set: WatchSet, rules: ScanRules) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let watch_adapter = WatchAdapter::new();
+
+        let (sender, receiver) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+        let debouncer = watch_adapter.create_debounced_watcher(DEBOUNCE_INTERVAL, move |paths| {
+            let _ = sender.send(paths);
+        })?;
+
+        let mut state = WatchState {
+            debouncer,
+            watched: HashSet::new(),
+            git_dirs: HashMap::new(),
+        };
+
+        // Repositories first so their state stays live even if the scan roots hit the cap
+        for (repo_root, git_dir) in &watch_set.repositories {
+            state.watch_repository(&watch_adapter, repo_root, git_dir);
+        }
+        for dir_path in &watch_set.directories {
+            if !state.watch(&watch_adapter, dir_path) && state.watched.len() >= MAX_WATCHED_DIRECTORIES {
+                println!("WATCH: Reached limit of {} watched directories", MAX_WATCHED_DIRECTORIES);
+                break;
+            }
+        }
+
+        println!("WATCH: Watching {} directories", state.watched.len());
+
+        let state = Arc::new(Mutex::new(state));
+        let weak_state = Arc::downgrade(&state);
+        tauri::async_runtime::spawn(Self::process_changes(app, weak_state, receiver, rules));
+
+        Ok(Self { state })
+    }
+
+    /// Number of directories currently being watched
+    pub fn watched_count(&self) -> usize {
+        self.state.lock().map(|state| state.watched.len()).unwrap_or(0)
+    }
+
+    async fn process_changes(app: AppHandle, state: Weak<Mutex<WatchState>>, mut receiver: mpsc::UnboundedReceiver<Vec<PathBuf>>, rules: ScanRules) {
+        let service = Arc::new(RepositoryService::new());
+        let watch_adapter = WatchAdapter::new();
+        let ignore_adapter = IgnoreAdapter::with_rules(&rules);
+
+        while let Some(paths) = receiver.recv().await {
+            // The watcher was dropped; stop processing
+            let Some(state) = state.upgrade() else { break };
+
+            let cache_service = match CacheService::new(app.clone()) {
+                Ok(cache_service) => cache_service,
+                Err(e) => {
+                    println!("WATCH: Failed to create cache service: {}", e);
+                    continue;
+                }
+            };
+            let Ok(Some(cache_data)) = cache_service.load_cache().await else {
+                continue;
+            };
+            let cached_repos = &cache_data.repositories;
+            let repo_roots: RepoRootSet = cached_repos.values()
+                .filter(|cached| cached.repository.is_git_repo)
+                .map(|cached| PathBuf::from(&cached.repository.path))
+                .collect();
+
+            let Ok(git_dirs) = state.lock().map(|state| state.git_dirs.clone()) else {
+                continue;
+            };
+
+            let mut to_refresh = HashSet::new();
+            let mut git_state_only = HashSet::new();
+            let mut removed = Vec::new();
+
+            for path in paths {
+                if let Some(repo_root) = Self::repo_root_for_git_file(&path, &git_dirs) {
+                    let is_state_file = path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| GIT_STATE_FILES.contains(&name));
+                    if is_state_file && cached_repos.contains_key(&*repo_root.to_string_lossy()) {
+                        git_state_only.insert(repo_root);
+                    } else {
+                        to_refresh.insert(repo_root);
+                    }
+                    continue;
+                }
+
+                // Ignore everything else inside a Git directory
+                if path.components().any(|c| c.as_os_str() == ".git")
+                    || path.ancestors().any(|ancestor| git_dirs.contains_key(ancestor))
+                {
+                    continue;
+                }
+
+                let path_str = path.to_string_lossy().to_string();
+                if path.is_dir() {
+                    // A new directory inside a known repository is part of it, not a new project
+                    if !cached_repos.contains_key(&path_str)
+                        && !repo_roots.contains_ancestor_of(&path)
+                        && !ignore_adapter.should_skip_directory(&path)
+                    {
+                        to_refresh.insert(path);
+                    }
+                } else if !path.exists() && cached_repos.contains_key(&path_str) {
+                    removed.push(path_str);
+                }
+            }
+
+            let git_state_repos: Vec<CachedRepository> = git_state_only.difference(&to_refresh)
+                .filter_map(|repo_root| cached_repos.get(&*repo_root.to_string_lossy()).cloned())
+                .collect();
+
+            // git2 and tokei block, so analyze off the async workers like a scan does
+            let analysis = {
+                let service = Arc::clone(&service);
+                let rules = rules.clone();
+                tokio::task::spawn_blocking(move || Handle::current().block_on(async {
+                    // Staging or fetching leaves the checkout alone; the previous fingerprint is
+                    // kept so the next scan still notices if the working tree itself changed
+                    let mut refreshed = Vec::new();
+                    for mut cached_repo in git_state_repos {
+                        service.refresh_git_state(&mut cached_repo.repository).await;
+                        refreshed.push(cached_repo);
+                    }
+
+                    let mut analyzed = Vec::new();
+                    for dir_path in to_refresh {
+                        let Some(repo) = service.analyze_directory(&dir_path, &rules, &ScanHandle::new()).await else {
+                            continue;
+                        };
+                        if service.is_project(&repo, &dir_path) {
+                            let fingerprint = service.collect_fingerprint(&dir_path);
+                            let git_dir = GitAdapter::new().git_dir(&dir_path).filter(|_| repo.is_git_repo);
+                            analyzed.push((dir_path, repo, fingerprint, git_dir));
+                        }
+                    }
+
+                    (refreshed, analyzed)
+                })).await
+            };
+            let (refreshed, analyzed) = match analysis {
+                Ok(analysis) => analysis,
+                Err(e) => {
+                    println!("WATCH: Failed to analyze changes: {}", e);
+                    continue;
+                }
+            };
+
+            let mut updated = Vec::new();
+            for cached_repo in refreshed {
+                let _ = app.emit("repo-changed", &cached_repo.repository);
+                updated.push(cached_repo);
+            }
+
+            for (dir_path, repo, fingerprint, git_dir) in analyzed {
+                if let Ok(mut state) = state.lock() {
+                    state.watch(&watch_adapter, &dir_path);
+                    if let Some(git_dir) = &git_dir {
+                        state.watch_repository(&watch_adapter, &dir_path, git_dir);
+                    }
+                }
+
+                let mut cached_repo = CachedRepository::new(repo.clone(), fingerprint.git_head_sha);
+                cached_repo.last_modified = fingerprint.last_modified;
+                cached_repo.worktree_signature = fingerprint.worktree_signature;
+                updated.push(cached_repo);
+
+                let _ = app.emit("repo-changed", &repo);
+            }
+
+            for repo_path in &removed {
+                if let Ok(mut state) = state.lock() {
+                    state.unwatch_repository(&watch_adapter, Path::new(repo_path));
+                }
+                let _ = app.emit("repo-removed", serde_json::json!({ "path": repo_path }));
+            }
+
+            if updated.is_empty() && removed.is_empty() {
+                continue;
+            }
+
+            println!("WATCH: Updating cache ({} changed, {} removed)", updated.len(), removed.len());
+            if let Err(e) = cache_service.update_entries(updated, &removed).await {
+                println!("WATCH: Failed to update cache: {}", e);
+            }
+        }
+
+        println!("WATCH: Stopped processing filesystem changes");
+    }
+
+    /// Map `<git dir>/HEAD`-style paths back to the repository root, using the watched
+    /// Git directories since a worktree's or submodule's lives outside its checkout
+    fn repo_root_for_git_file(path: &Path, git_dirs: &HashMap<PathBuf, PathBuf>) -> Option<PathBuf> {
+        GIT_TRIGGER_FILES.iter()
+            .filter(|trigger| path.ends_with(trigger))
+            .filter_map(|trigger| path.ancestors().nth(Path::new(trigger).components().count()))
+            .find_map(|git_dir| git_dirs.get(git_dir))
+            .cloned()
+    }
+}
@@ -16,6 +16,15 @@ import { StatsOverview } from "./components/StatsOverview";
 import { BatchOperations } from "./components/BatchOperations";
 import { Repository, DirectoryStats, FilterOptions } from "./types";
 
+// Repos with uncommitted changes or no remotes; those stuck mid-merge/rebase or with conflicts first
+function reposNeedingAttention(gitRepos: Repository[]): Repository[] {
+  const isStuck = (r: Repository) => r.operation_in_progress !== null || r.conflicted_files.length > 0;
+  return gitRepos
+    .filter(r => r.has_uncommitted_changes || r.remotes.length === 0 || isStuck(r))
+    .sort((a, b) => Number(isStuck(b)) - Number(isStuck(a)))
+    .slice(0, 20);
+}
+
 function App() {
   const [repositories, setRepositories] = useState<Repository[]>([]);
   const [stats, setStats] = useState<DirectoryStats | null>(null);
@@ -26,6 +35,7 @@ function App() {
   const [loading, setLoading] = useState(false);
   const [scanProgress, setScanProgress] = useState<string>("");
   const [isScanning, setIsScanning] = useState(false); // Scan lock mechanism
+  const [activeScanId, setActiveScanId] = useState<string | null>(null);
   const [selectedRepos, setSelectedRepos] = useState<Set<string>>(new Set());
   const [activeView, setActiveView] = useState<'overview' | 'repositories' | 'batch'>('overview');
   const [filters, setFilters] = useState<FilterOptions>({
@@ -49,7 +59,13 @@ function App() {
           
           // Set up progress listener
           listen('scan-progress', (event: any) => {
-            const { current_directory, scanned_count, total_count } = event.payload;
+            const { scan_id, cancelled, current_directory, scanned_count, total_count } = event.payload;
+            if (cancelled) {
+              setActiveScanId(null);
+              setScanProgress("Scan cancelled");
+              return;
+            }
+            setActiveScanId(scan_id);
             // Show full path in a shortened form
             const pathParts = current_directory.split('/');
             const displayPath = pathParts.length > 3 
@@ -57,6 +73,18 @@ function App() {
               : current_directory;
             setScanProgress(`Analyzing (${scanned_count}/${total_count}): ${displayPath}`);
           });
+
+          // Keep the list live as the backend watcher picks up filesystem changes
+          listen<Repository>('repo-changed', (event) => {
+            const changed = event.payload;
+            setRepositories(prev => {
+              const others = prev.filter(r => r.path !== changed.path);
+              return [...others, changed].sort((a, b) => a.name.localeCompare(b.name));
+            });
+          });
+          listen<{ path: string }>('repo-removed', (event) => {
+            setRepositories(prev => prev.filter(r => r.path !== event.payload.path));
+          });
           
           // Try to load cached repositories on app startup
           try {
@@ -82,9 +110,11 @@ function App() {
                   const bTime = b.last_commit_date ? new Date(b.last_commit_date).getTime() : 0;
                   return bTime - aTime;
                 }).slice(0, 10),
-                repos_needing_attention: gitRepos.filter(r => 
-                  r.has_uncommitted_changes || r.remotes.length === 0
-                ).slice(0, 20)
+                repos_needing_attention: reposNeedingAttention(gitRepos),
+                repos_behind_default: gitRepos
+                  .filter(r => !r.is_default_branch && r.behind_default > 0)
+                  .sort((a, b) => b.behind_default - a.behind_default)
+                  .slice(0, 10)
               };
               
               setStats(directoryStats);
@@ -235,9 +265,11 @@ function App() {
           const bTime = b.last_commit_date ? new Date(b.last_commit_date).getTime() : 0;
           return bTime - aTime;
         }).slice(0, 10),
-        repos_needing_attention: gitRepos.filter(r => 
-          r.has_uncommitted_changes || r.remotes.length === 0
-        ).slice(0, 20)
+        repos_needing_attention: reposNeedingAttention(gitRepos),
+        repos_behind_default: gitRepos
+          .filter(r => !r.is_default_branch && r.behind_default > 0)
+          .sort((a, b) => b.behind_default - a.behind_default)
+          .slice(0, 10)
       };
       
       console.log("Scan results:", { totalRepos: allRepos.length, stats: directoryStats });
@@ -261,6 +293,7 @@ function App() {
     } finally {
       // Release scan lock
       setIsScanning(false);
+      setActiveScanId(null);
       setLoading(false);
       setTimeout(() => setScanProgress(""), 3000); // Clear progress after 3 seconds
     }
@@ -350,9 +383,11 @@ function App() {
           const bTime = b.last_commit_date ? new Date(b.last_commit_date).getTime() : 0;
           return bTime - aTime;
         }).slice(0, 10),
-        repos_needing_attention: gitRepos.filter(r => 
-          r.has_uncommitted_changes || r.remotes.length === 0
-        ).slice(0, 20)
+        repos_needing_attention: reposNeedingAttention(gitRepos),
+        repos_behind_default: gitRepos
+          .filter(r => !r.is_default_branch && r.behind_default > 0)
+          .sort((a, b) => b.behind_default - a.behind_default)
+          .slice(0, 10)
       };
       
       setRepositories(allRepos);
@@ -361,6 +396,7 @@ function App() {
       
       // Release scan lock
       setIsScanning(false);
+      setActiveScanId(null);
       setLoading(false);
       setTimeout(() => setScanProgress(""), 2000);
     }
@@ -429,6 +465,14 @@ function App() {
                   <p className="font-mono">{scanProgress}</p>
                 </div>
               )}
+              {activeScanId && (
+                <button
+                  onClick={() => core.invoke("cancel_scan", { scanId: activeScanId }).catch(console.error)}
+                  className="mt-4 px-4 py-2 text-sm border border-border rounded-lg hover:bg-accent transition-colors"
+                >
+                  Cancel Scan
+                </button>
+              )}
             </div>
           </div>
         )}
@@ -1,6 +1,7 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { Repository, BatchOperation, BatchResult, BatchOperationType } from "../types";
+import { listen } from "@tauri-apps/api/event";
+import { Repository, BatchOperation, BatchResult, BatchOperationType, BatchProgress } from "../types";
 import { 
   Play, 
   Download, 
@@ -9,7 +10,8 @@ import {
   GitPullRequest,
   CheckCircle,
   XCircle,
-  Terminal
+  Terminal,
+  Layers
 } from "lucide-react";
 
 interface BatchOperationsProps {
@@ -22,6 +24,20 @@ export function BatchOperations({ repositories, selectedRepos, onRefresh }: Batc
   const [loading, setLoading] = useState(false);
   const [result, setResult] = useState<BatchResult | null>(null);
   const [customCommand, setCustomCommand] = useState("");
+  const [progress, setProgress] = useState<Record<string, BatchProgress>>({});
+
+  useEffect(() => {
+    const unlisten = listen<BatchProgress>("batch-progress", (event) => {
+      setProgress((prev) => ({ ...prev, [event.payload.repo_path]: event.payload }));
+    });
+
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const progressEntries = Object.values(progress);
+  const completedCount = progressEntries.reduce((max, p) => Math.max(max, p.completed_count), 0);
 
   const selectedRepositories = repositories.filter(repo => selectedRepos.has(repo.path));
 
@@ -30,6 +46,7 @@ export function BatchOperations({ repositories, selectedRepos, onRefresh }: Batc
 
     setLoading(true);
     setResult(null);
+    setProgress({});
 
     try {
       const operation: BatchOperation = {
@@ -45,10 +62,20 @@ export function BatchOperations({ repositories, selectedRepos, onRefresh }: Batc
       setResult(batchResult);
       
       // Refresh the repositories list after operations that might change status
-      if (['Pull', 'Push', 'Fetch'].includes(operationType)) {
+      if (['Pull', 'Push', 'Fetch', 'SubmoduleUpdate'].includes(operationType)) {
         setTimeout(onRefresh, 1000);
       }
     } catch (error) {
+      // Destructive custom commands (e.g. push --force) must be confirmed explicitly
+      if (operationType === 'Custom' && !parameters.confirmed && String(error).startsWith("Confirmation required")) {
+        if (window.confirm(`${error}\n\nRun it on ${selectedRepos.size} repositories anyway?`)) {
+          setLoading(false);
+          await executeOperation(operationType, { ...parameters, confirmed: true });
+          return;
+        }
+      } else {
+        alert(`Batch operation failed: ${error}`);
+      }
       console.error("Batch operation failed:", error);
     } finally {
       setLoading(false);
@@ -57,40 +84,47 @@ export function BatchOperations({ repositories, selectedRepos, onRefresh }: Batc
 
   const operations = [
     {
-      id: 'pull' as BatchOperationType,
+      id: 'Pull' as BatchOperationType,
       label: 'Pull',
       description: 'Pull latest changes from remote',
       icon: Download,
       className: 'bg-blue-500 hover:bg-blue-600',
     },
     {
-      id: 'push' as BatchOperationType,
+      id: 'Push' as BatchOperationType,
       label: 'Push',
       description: 'Push local commits to remote',
       icon: Upload,
       className: 'bg-green-500 hover:bg-green-600',
     },
     {
-      id: 'fetch' as BatchOperationType,
+      id: 'Fetch' as BatchOperationType,
       label: 'Fetch',
       description: 'Fetch latest refs from remote',
       icon: RefreshCw,
       className: 'bg-purple-500 hover:bg-purple-600',
     },
     {
-      id: 'status' as BatchOperationType,
+      id: 'Status' as BatchOperationType,
       label: 'Status',
       description: 'Get working directory status',
       icon: GitPullRequest,
       className: 'bg-orange-500 hover:bg-orange-600',
     },
+    {
+      id: 'SubmoduleUpdate' as BatchOperationType,
+      label: 'Submodules',
+      description: 'Initialize and update submodules',
+      icon: Layers,
+      className: 'bg-teal-500 hover:bg-teal-600',
+    },
   ];
 
   const executeCustomCommand = async () => {
     if (!customCommand.trim() || selectedRepos.size === 0) return;
 
-    const commandParts = customCommand.trim().split(/\s+/);
-    await executeOperation('Custom', { command: commandParts });
+    // Sent as typed; the backend splits it with shell quoting rules
+    await executeOperation('Custom', { command: customCommand.trim() });
   };
 
   return (
@@ -198,7 +232,7 @@ export function BatchOperations({ repositories, selectedRepos, onRefresh }: Batc
               </div>
               
               <p className="text-xs text-muted-foreground mt-2">
-                Execute any git command on selected repositories. Command will be run in each repository's directory.
+                Execute a git command on selected repositories. Command will be run in each repository's directory; destructive commands ask for confirmation first.
               </p>
             </div>
           </>
@@ -209,8 +243,24 @@ export function BatchOperations({ repositories, selectedRepos, onRefresh }: Batc
           <div className="bg-card border border-border rounded-lg p-6">
             <div className="flex items-center justify-center gap-3">
               <div className="w-6 h-6 border-2 border-primary border-t-transparent rounded-full animate-spin"></div>
-              <span>Executing batch operation...</span>
+              <span>Executing batch operation... ({completedCount}/{selectedRepos.size})</span>
             </div>
+
+            {progressEntries.length > 0 && (
+              <div className="mt-4 max-h-48 overflow-auto text-sm space-y-1">
+                {progressEntries.map((p) => (
+                  <div key={p.repo_path} className="flex items-center justify-between py-1 px-2 bg-muted rounded">
+                    <span className="font-medium">{p.repo_path.split('/').pop()}</span>
+                    <span className="flex items-center gap-1 text-xs text-muted-foreground">
+                      {p.state === 'Started' && <RefreshCw className="w-3 h-3 animate-spin" />}
+                      {p.state === 'Succeeded' && <CheckCircle className="w-3 h-3 text-green-600" />}
+                      {p.state === 'Failed' && <XCircle className="w-3 h-3 text-red-600" />}
+                      {p.state === 'Started' ? 'Running' : `${p.elapsed_ms} ms`}
+                    </span>
+                  </div>
+                ))}
+              </div>
+            )}
           </div>
         )}
 
@@ -248,6 +298,9 @@ export function BatchOperations({ repositories, selectedRepos, onRefresh }: Batc
                     <span className="font-medium">
                       {r.repo_path.split('/').pop()}
                     </span>
+                    <span className="text-xs text-muted-foreground ml-auto">
+                      {r.elapsed_ms} ms
+                    </span>
                   </div>
                   
                   {r.output && (
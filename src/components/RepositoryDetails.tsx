@@ -118,11 +118,32 @@ export function RepositoryDetails({ repository, onClose }: RepositoryDetailsProp
         {repository.commit_count !== null && (
           <div className="flex justify-between text-sm">
             <span className="text-muted-foreground">Commits</span>
-            <span className="font-medium">{repository.commit_count}</span>
+            <span className="font-medium">
+              {repository.commit_count}{repository.commit_count_truncated && "+"}
+            </span>
           </div>
         )}
       </div>
 
+      {repository.sub_projects.length > 0 && (
+        <div className="p-4 border-b border-border">
+          <h4 className="font-medium mb-2">Sub-projects ({repository.sub_projects.length})</h4>
+          <div className="space-y-1 max-h-40 overflow-auto">
+            {repository.sub_projects.map((subProject) => (
+              <div key={subProject.path} className="flex justify-between gap-2 text-sm" title={subProject.path}>
+                <span className="truncate">
+                  {subProject.name}
+                  <span className="text-muted-foreground ml-2">{subProject.relative_path}</span>
+                </span>
+                <span className="text-muted-foreground whitespace-nowrap">
+                  {subProject.primary_language || 'Unknown'} · {subProject.code_lines.toLocaleString()} lines
+                </span>
+              </div>
+            ))}
+          </div>
+        </div>
+      )}
+
       {repository.is_git_repo ? (
         <>
           {/* Tabs */}
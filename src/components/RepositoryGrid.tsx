@@ -38,8 +38,15 @@ export function RepositoryGrid({
 }: RepositoryGridProps) {
   const [showFilters, setShowFilters] = useState(false);
 
+  // Worktrees are listed under their main repository when it was scanned too
+  const repoByPath = new Map(repositories.map(repo => [repo.path, repo]));
+  const isGroupedWorktree = (repo: Repository) =>
+    typeof repo.repo_kind === 'object' && repo.repo_kind !== null && 'Worktree' in repo.repo_kind
+      && repoByPath.has(repo.repo_kind.Worktree.main_repo);
+
   // Apply filters
   const filteredRepos = repositories.filter(repo => {
+    if (isGroupedWorktree(repo)) return false;
     if (filters.showGitOnly && !repo.is_git_repo) return false;
     if (filters.showWithChanges && !repo.has_uncommitted_changes) return false;
     if (filters.showWithoutRemotes && repo.remotes.length > 0) return false;
@@ -57,8 +64,10 @@ export function RepositoryGrid({
       case 'size':
         return order * (a.size_mb - b.size_mb);
       case 'activity':
-        const aTime = a.last_activity ? new Date(a.last_activity).getTime() : 0;
-        const bTime = b.last_activity ? new Date(b.last_activity).getTime() : 0;
+        const aActivity = a.last_commit_date ?? a.last_activity;
+        const bActivity = b.last_commit_date ?? b.last_activity;
+        const aTime = aActivity ? new Date(aActivity).getTime() : 0;
+        const bTime = bActivity ? new Date(bActivity).getTime() : 0;
         return order * (bTime - aTime);
       case 'status':
         return order * a.status.toString().localeCompare(b.status.toString());
@@ -279,6 +288,9 @@ export function RepositoryGrid({
                           {repo.current_branch || 'No branch'}
                         </div>
                       )}
+                      {repo.repo_kind === 'Bare' && (
+                        <span className="px-2 py-1 rounded text-xs font-medium text-gray-600 bg-gray-100">Bare</span>
+                      )}
                     </div>
 
                     <div className="flex items-center justify-between text-xs text-muted-foreground">
@@ -297,6 +309,32 @@ export function RepositoryGrid({
                         {repo.remotes.length} remote{repo.remotes.length !== 1 ? 's' : ''}
                       </div>
                     )}
+
+                    {repo.worktrees.length > 0 && (
+                      <div className="border-t border-border pt-2 space-y-1">
+                        {repo.worktrees.map((worktree) => {
+                          const worktreeRepo = repoByPath.get(worktree.path);
+                          return (
+                            <div
+                              key={worktree.path}
+                              className={`flex items-center gap-1 text-xs text-muted-foreground ${worktreeRepo ? 'hover:text-foreground' : ''}`}
+                              title={worktree.path}
+                              onClick={(e) => {
+                                if (!worktreeRepo) return;
+                                e.stopPropagation();
+                                onRepoSelect(worktreeRepo);
+                              }}
+                            >
+                              <Folder className="w-3 h-3" />
+                              <span className="truncate">{worktree.name}</span>
+                              <span>·</span>
+                              <span className="truncate">{worktree.branch || 'detached'}</span>
+                              {worktree.is_missing && <span className="text-red-600">(missing)</span>}
+                            </div>
+                          );
+                        })}
+                      </div>
+                    )}
                   </div>
                 </div>
               );
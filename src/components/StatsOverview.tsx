@@ -100,6 +100,12 @@ export function StatsOverview({ stats, onNavigateToRepos }: StatsOverviewProps)
                       <span className={`px-2 py-1 rounded text-xs font-medium ${status.className}`}>
                         {status.text}
                       </span>
+                      {(repo.operation_in_progress || repo.conflicted_files.length > 0) && (
+                        <span className="px-2 py-1 rounded text-xs font-medium text-red-600 bg-red-100">
+                          {repo.operation_in_progress ? `${repo.operation_in_progress} in progress` : 'Conflicts'}
+                          {repo.conflicted_files.length > 0 && ` (${repo.conflicted_files.length} conflicted)`}
+                        </span>
+                      )}
                       <div className="text-sm text-muted-foreground">
                         {repo.remotes.length === 0 ? 'No remotes' : `${repo.remotes.length} remote(s)`}
                       </div>
@@ -121,6 +127,34 @@ export function StatsOverview({ stats, onNavigateToRepos }: StatsOverviewProps)
           </div>
         )}
 
+        {/* Feature Branches Behind Default */}
+        {stats.repos_behind_default?.length > 0 && (
+          <div className="bg-card border border-border rounded-lg">
+            <div className="p-4 border-b border-border">
+              <div className="flex items-center gap-2">
+                <GitBranch className="w-5 h-5 text-blue-600" />
+                <h2 className="text-lg font-semibold">Behind Default Branch</h2>
+              </div>
+            </div>
+            <div className="p-4 space-y-3">
+              {stats.repos_behind_default.map((repo) => (
+                <div key={repo.path} className="flex items-center justify-between py-2 border-b border-border last:border-0">
+                  <div className="flex-1">
+                    <div className="font-medium">{repo.name}</div>
+                    <div className="text-sm text-muted-foreground truncate" title={repo.path}>
+                      {repo.current_branch || 'Detached HEAD'}
+                    </div>
+                  </div>
+                  <div className="text-sm text-muted-foreground">
+                    {repo.behind_default} behind {repo.default_branch}
+                    {repo.ahead_of_default > 0 && `, ${repo.ahead_of_default} ahead`}
+                  </div>
+                </div>
+              ))}
+            </div>
+          </div>
+        )}
+
         {/* Two Column Layout for Lists */}
         <div className="grid grid-cols-1 lg:grid-cols-2 gap-6">
           {/* Largest Repositories */}
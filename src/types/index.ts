@@ -10,11 +10,64 @@ export interface Repository {
   status: RepoStatus;
   size_mb: number;
   commit_count: number | null;
+  commit_count_truncated: boolean;
   primary_language: string | null;
   total_lines: number;
   code_lines: number;
+  ahead: number;
+  behind: number;
+  default_branch: string | null;
+  is_default_branch: boolean;
+  ahead_of_default: number;
+  behind_default: number;
+  operation_in_progress: GitOperation | null;
+  conflicted_files: string[];
+  stash_count: number;
+  submodules: SubmoduleInfo[];
+  repo_kind: RepoKind | null;
+  worktrees: WorktreeInfo[];
+  sub_projects: SubProject[];
 }
 
+export type RepoKind =
+  | "Normal"
+  | "Bare"
+  | { Worktree: { main_repo: string } }
+  | { Submodule: { parent_repo: string } };
+
+export type WorkspaceKind = "Cargo" | "Npm" | "Pnpm" | "Go" | "Maven";
+
+export interface SubProject {
+  name: string;
+  path: string;
+  relative_path: string;
+  workspace: WorkspaceKind;
+  primary_language: string | null;
+  total_lines: number;
+  code_lines: number;
+}
+
+export interface WorktreeInfo {
+  name: string;
+  path: string;
+  branch: string | null;
+  head_sha: string | null;
+  is_locked: boolean;
+  is_missing: boolean;
+}
+
+export interface SubmoduleInfo {
+  name: string;
+  path: string;
+  url: string | null;
+  recorded_sha: string | null;
+  checked_out_sha: string | null;
+  is_initialized: boolean;
+  is_dirty: boolean;
+}
+
+export type GitOperation = "Merge" | "Rebase" | "CherryPick" | "Revert" | "Bisect" | "ApplyMailbox";
+
 export type RepoStatus = 
   | "Clean" 
   | "Dirty" 
@@ -31,6 +84,26 @@ export interface GitStatus {
   behind: number;
   current_branch: string | null;
   tracking_branch: string | null;
+  operation_in_progress: GitOperation | null;
+  conflicted_files: string[];
+  files: FileStatusEntry[];
+}
+
+export interface FileStatusEntry {
+  path: string;
+  old_path: string | null;
+  index_status: FileChangeStatus | null;
+  worktree_status: FileChangeStatus | null;
+  is_conflicted: boolean;
+  is_ignored: boolean;
+  size_bytes: number | null;
+  submodule: SubmoduleChanges | null;
+}
+
+export interface SubmoduleChanges {
+  new_commits: boolean;
+  modified_content: boolean;
+  untracked_content: boolean;
 }
 
 export interface RemoteInfo {
@@ -59,6 +132,47 @@ export interface RepoActivity {
   files_changed_last_month: number;
   lines_added_last_month: number;
   lines_deleted_last_month: number;
+  window_days: number;
+  truncated: boolean;
+}
+
+export interface CommitHeatmap {
+  start_date: string;
+  end_date: string;
+  weeks: number[][];
+  total_commits: number;
+  max_daily_commits: number;
+  repositories: number;
+  truncated_repositories: string[];
+}
+
+export interface ContributionReport {
+  window_days: number;
+  generated_at: string;
+  repositories_analyzed: number;
+  failed_repositories: string[];
+  truncated_repositories: string[];
+  authors: AuthorContribution[];
+}
+
+export interface AuthorContribution {
+  name: string;
+  email: string;
+  commits: number;
+  lines_added: number;
+  lines_deleted: number;
+  active_days: number;
+  last_commit_date: string | null;
+  repositories: AuthorRepoContribution[];
+}
+
+export interface AuthorRepoContribution {
+  repo_name: string;
+  repo_path: string;
+  commits: number;
+  lines_added: number;
+  lines_deleted: number;
+  active_days: number;
 }
 
 export interface BatchOperation {
@@ -72,6 +186,7 @@ export type BatchOperationType =
   | "Status" 
   | "Fetch" 
   | "Commit" 
+  | "SubmoduleUpdate" 
   | "Custom";
 
 export interface BatchResult {
@@ -86,6 +201,42 @@ export interface BatchOperationResult {
   success: boolean;
   output: string;
   error: string | null;
+  elapsed_ms: number;
+}
+
+export type BatchRepoState = "Started" | "Succeeded" | "Failed";
+
+export interface BatchProgress {
+  batch_id: string;
+  repo_path: string;
+  state: BatchRepoState;
+  output: string | null;
+  error: string | null;
+  elapsed_ms: number;
+  completed_count: number;
+  total_count: number;
+}
+
+export interface GitCommandRule {
+  subcommand: string;
+  args: string[];
+}
+
+export interface GitCommandPolicy {
+  allowed_subcommands: string[];
+  denied: GitCommandRule[];
+  confirm_required: GitCommandRule[];
+  timeout_secs: number;
+}
+
+export interface GitCommandResult {
+  command: string[];
+  success: boolean;
+  exit_code: number | null;
+  stdout: string;
+  stderr: string;
+  timed_out: boolean;
+  elapsed_ms: number;
 }
 
 export interface DirectoryStats {
@@ -98,6 +249,7 @@ export interface DirectoryStats {
   largest_repos: Repository[];
   most_active_repos: Repository[];
   repos_needing_attention: Repository[];
+  repos_behind_default: Repository[];
 }
 
 export interface FilterOptions {
@@ -114,4 +266,151 @@ export interface ScanProgress {
   processed: number;
   total: number | null;
   percentage: number | null;
-}
\ No newline at end of file
+}
+export type StaleReason = "Merged" | "UpstreamGone" | "Inactive";
+
+export interface StaleBranch {
+  repo_path: string;
+  branch: string;
+  tip_sha: string;
+  last_commit: string | null;
+  upstream: string | null;
+  is_current: boolean;
+  reasons: StaleReason[];
+}
+
+export interface StaleBranchReport {
+  generated_at: string;
+  inactive_days: number;
+  repositories_analyzed: number;
+  failed_repositories: string[];
+  branches: StaleBranch[];
+}
+
+export interface BranchDeletionRequest {
+  repo_path: string;
+  branch: string;
+  tip_sha: string;
+}
+
+export interface BranchCleanupResult {
+  dry_run: boolean;
+  deleted: number;
+  failed: number;
+  results: BranchDeletionResult[];
+}
+
+export interface BranchDeletionResult {
+  repo_path: string;
+  branch: string;
+  success: boolean;
+  tip_sha: string | null;
+  error: string | null;
+}
+
+export interface DeletedBranchRecord {
+  repo_path: string;
+  branch: string;
+  tip_sha: string;
+  upstream: string | null;
+  deleted_at: string;
+}
+
+export interface StashInfo {
+  repo_path: string;
+  index: number;
+  sha: string;
+  message: string;
+  branch: string | null;
+  date: string | null;
+  file_count: number;
+}
+
+export interface StashReport {
+  generated_at: string;
+  older_than_days: number;
+  repositories_analyzed: number;
+  failed_repositories: string[];
+  stashes: StashInfo[];
+}
+
+export type FileChangeStatus =
+  | "Unmodified"
+  | "Added"
+  | "Deleted"
+  | "Modified"
+  | "Renamed"
+  | "Copied"
+  | "TypeChange"
+  | "Untracked"
+  | "Conflicted";
+
+export interface FileDiff {
+  path: string;
+  old_path: string | null;
+  status: FileChangeStatus;
+  is_binary: boolean;
+  lines_added: number;
+  lines_deleted: number;
+  hunks: DiffHunk[];
+}
+
+export interface DiffHunk {
+  header: string;
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+  lines: DiffLine[];
+}
+
+export type DiffLineKind = "Context" | "Added" | "Removed";
+
+export interface DiffLine {
+  kind: DiffLineKind;
+  old_lineno: number | null;
+  new_lineno: number | null;
+  content: string;
+  missing_newline: boolean;
+}
+
+export interface HunkRange {
+  old_start: number;
+  old_lines: number;
+  new_start: number;
+  new_lines: number;
+}
+
+export interface CommitResult {
+  sha: string;
+  summary: string;
+  branch: string | null;
+  output: string;
+}
+
+export interface DiscardBackup {
+  repo_path: string;
+  backup_path: string;
+  files: string[];
+  discarded_at: string;
+}
+
+export interface ScanRules {
+  exclude: string[];
+  not_projects: string[];
+  include: string[];
+  never_skip: string[];
+  root_overrides: ScanRootRules[];
+  depth: ScanDepth;
+  sub_projects: boolean;
+}
+
+export type ScanDepth = { Levels: number } | "UntilRepository";
+
+export interface ScanRootRules {
+  root: string;
+  exclude: string[];
+  not_projects: string[];
+  include: string[];
+  depth: ScanDepth | null;
+}